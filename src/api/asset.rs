@@ -8,12 +8,16 @@ use serde::{Deserialize, Serialize};
 use serde::de::Deserializer;
 
 use crate::error::{KError, KrakenErrors};
+use crate::registry;
 
 // TODO: Query AssetInfo endpoint and write script to fill out the
 // enum and trait impl
 /// Assets accepted on the Kraken Exchange
 /// # FIXME
 /// Basic currencies used for testing. Open pull request to add more currencies <https://github.com/Fuzzy-Math/KrakenAPI-Rust>
+///
+/// Assets missing from this list are parsed into [KAsset::Other] once an
+/// [AssetRegistry][crate::registry::AssetRegistry] listing them is installed
 #[derive(Serialize, PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum KAsset {
     AAVE,
@@ -101,6 +105,10 @@ pub enum KAsset {
     XtzS,
     YFI,
     ZEC,
+    /// Asset without a variant of its own, named by its alternate name, i.e. `PEPE`. Only
+    /// produced by an [AssetRegistry][crate::registry::AssetRegistry]
+    #[serde(untagged)]
+    Other(&'static str),
 }
 
 impl Display for KAsset {
@@ -187,6 +195,7 @@ impl Display for KAsset {
             KAsset::XtzS => write!(f, "XTZ.S"),
             KAsset::YFI => write!(f, "YFI"),
             KAsset::ZEC => write!(f, "ZEC"),
+            KAsset::Other(name) => write!(f, "{}", name),
         }
     }
 }
@@ -277,14 +286,15 @@ impl FromStr for KAsset {
             "XTZ" => Ok(KAsset::XTZ),
             "XTZ.S" => Ok(KAsset::XtzS),
             "YFI" => Ok(KAsset::YFI),
-            _     => Err(KrakenErrors(vec![KError::AssetParseError])),
+            other => registry::lookup_asset(other)
+                .ok_or_else(|| KrakenErrors(vec![KError::AssetParseError])),
         }
     }
 }
 
 impl Debug for KAsset {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}", self)
     }
 }
 
@@ -299,12 +309,11 @@ impl<'de> Deserialize<'de> for KAsset {
 }
 
 /// Tradeable asset pair
-/// # FIXME
-/// Kraken only accepts certain asset pairs as listed from the asset pair endpoint.
-/// This data probably should be parsed into a lookup table to ensure only support pairs are
-/// accepted.
-/// Open a pull request at <https://github.com/Fuzzy-Math/KrakenAPI-Rust>
-#[derive(Serialize, Hash, Clone, Copy)]
+///
+/// Pair names are resolved through the installed
+/// [AssetRegistry][crate::registry::AssetRegistry] first. Without one, or for pairs it doesn't
+/// list, the split between base and quote asset is guessed from the length of the name
+#[derive(Serialize, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct KAssetPair(
     //#[serde(deserialize_with = "deserialize_asset")]
     pub KAsset, 
@@ -312,30 +321,44 @@ pub struct KAssetPair(
     pub KAsset
 );
 
-impl Display for KAssetPair {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", self.0.to_string(), self.1.to_string())
+impl KAssetPair {
+    /// Name of this pair as used by Kraken's websocket API, i.e. `XBT/USD`
+    pub fn wsname(&self) -> String {
+        format!("{}/{}", self.0, self.1)
+    }
+
+    /// Parse a websocket pair name such as `XBT/USD` into a KAssetPair
+    pub fn from_wsname(val: &str) -> Result<Self, KrakenErrors<KError>> {
+        if let Some(pair) = registry::lookup_pair(val) {
+            return Ok(pair);
+        }
+        match val.split_once('/') {
+            Some((base, quote)) => Ok(KAssetPair(base.parse::<KAsset>()?, quote.parse::<KAsset>()?)),
+            None => Err(KrakenErrors(vec![KError::AssetParseError])),
+        }
     }
 }
 
-impl Debug for KAssetPair {
+impl Display for KAssetPair {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\"{}{}\"", self.0.to_string(), self.1.to_string())
+        write!(f, "{}{}", self.0, self.1)
     }
 }
 
-impl PartialEq for KAssetPair {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0 && self.1 == other.1
+impl Debug for KAssetPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}{}\"", self.0, self.1)
     }
 }
 
-impl Eq for KAssetPair {}
-
 impl FromStr for KAssetPair {
     type Err = KrakenErrors<KError>;
 
     fn from_str(val: &str) -> Result<Self, Self::Err> {
+        if let Some(pair) = registry::lookup_pair(val) {
+            return Ok(pair);
+        }
+
         // Take in a &str, say "XXBTZUSD"
         // Drop the currency type characters at index 0 and 4 ('Z' = fiat, 'X' = crypto)
         // Now we have "XBTUSD"
@@ -346,38 +369,33 @@ impl FromStr for KAssetPair {
         match val.len() {
             // We know this is pairs with KAsset::SC as the base currency. A 2/3 split
             5 => {
-                println!("base: {}, quote: {}\n", (&val[..2]), (&val[2..]));
-                Ok(KAssetPair((&val[..2]).parse::<KAsset>()?, (&val[2..]).parse::<KAsset>()?))
+                Ok(KAssetPair(val[..2].parse::<KAsset>()?, val[2..].parse::<KAsset>()?))
             },
 
             // Has to be split 3/3. It can't be split 2/4 since that would imply SC is the base
             // currency but we know all pairs with SC are of length 5
             6 => {
-                println!("base: {}, quote: {}\n", (&val[..3]), (&val[3..]));
-                Ok(KAssetPair((&val[..3]).parse::<KAsset>()?, (&val[3..]).parse::<KAsset>()?))
+                Ok(KAssetPair(val[..3].parse::<KAsset>()?, val[3..].parse::<KAsset>()?))
             },
 
             // More the likely split 4/3. If that fails to parse, split it 3/4 and parse again
             7 => {
                 if let (Ok(base), Ok(quote)) = 
                     (
-                        (&val[..4]).parse::<KAsset>(), 
-                        (&val[4..]).parse::<KAsset>()
+                        val[..4].parse::<KAsset>(), 
+                        val[4..].parse::<KAsset>()
                     )
                 {
-                    println!("base: {}, quote: {}\n", &val[..4], &val[4..]);
                     Ok(KAssetPair(base, quote))
                 } else {
                     if let (Ok(base), Ok(quote)) = 
                         (
-                            (&val[..3]).parse::<KAsset>(), 
-                            (&val[3..]).parse::<KAsset>()
+                            val[..3].parse::<KAsset>(), 
+                            val[3..].parse::<KAsset>()
                         )
                     {
                         Ok(KAssetPair(base, quote))
                     } else {
-                        println!("Length 7: {}", val);
-                        println!("base: {}, quote: {}\n", &val[..3], &val[3..]);
                         Err(KrakenErrors(vec![KError::AssetParseError]))
                     }
                 }
@@ -388,32 +406,28 @@ impl FromStr for KAssetPair {
             8 => {
                 if let (Ok(base), Ok(quote)) = 
                     (
-                        (&val[..4]).parse::<KAsset>(), 
-                        (&val[4..]).parse::<KAsset>()
+                        val[..4].parse::<KAsset>(), 
+                        val[4..].parse::<KAsset>()
                     )
                 {
-                    println!("base: {}, quote: {}\n", &val[..4], &val[4..]);
                     Ok(KAssetPair(base, quote))
                 } else { 
                     if let (Ok(base), Ok(quote)) = 
                         (
-                            (&val[..5]).parse::<KAsset>(), 
-                            (&val[5..]).parse::<KAsset>()
+                            val[..5].parse::<KAsset>(), 
+                            val[5..].parse::<KAsset>()
                         )
                     {
-                        println!("base: {}, quote: {}\n", &val[..5], &val[5..]);
                         Ok(KAssetPair(base, quote))
                     } else {
                         if let (Ok(base), Ok(quote)) =
                             (
-                                (&val[..3]).parse::<KAsset>(),
-                                (&val[3..]).parse::<KAsset>()
+                                val[..3].parse::<KAsset>(),
+                                val[3..].parse::<KAsset>()
                             )
                         {
                             Ok(KAssetPair(base, quote))
                         } else {
-                            println!("Length 8: {}", val);
-                            println!("base: {}, quote: {}\n", &val[..3], &val[3..]);
                             Err(KrakenErrors(vec![KError::AssetParseError]))
                         }
                     }
@@ -426,11 +440,10 @@ impl FromStr for KAssetPair {
             // Don't really know what the pairs that end in ".d" are
             // Just going to chop of the ".d" and pass it back recursively into the parser
             10 => {
-                (&val[..8]).parse::<KAssetPair>()                
+                val[..8].parse::<KAssetPair>()                
             }
             // We don't know what we got, Kraken probably changed their api if we are hitting this
             _ => {
-                println!("Unknown pair: {}\n", &val);
                 Err(KrakenErrors(vec![KError::UnknownAssetPair]))
             },
         }
@@ -441,7 +454,7 @@ impl TryFrom<&str> for KAssetPair {
     type Error = KrakenErrors<KError>;
 
     fn try_from(val: &str) -> Result<Self, Self::Error> {
-        FromStr::from_str(&val)
+        FromStr::from_str(val)
     }
 }
 
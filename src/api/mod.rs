@@ -2,6 +2,7 @@
 
 use indexmap::map::IndexMap;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fmt;
 use std::fmt::{Debug, Display};
 
@@ -16,6 +17,179 @@ pub mod public;
 /// [KError][super::error::KError]'s
 pub type KrakenResult<T> = Result<T, KrakenErrors<KError>>;
 
+/// Type of the prices, volumes and amounts in output structs. Kraken sends these as strings to
+/// avoid any loss of precision, which are kept as is by default. With the `decimal` feature
+/// enabled they are parsed into exact `rust_decimal::Decimal` values instead
+#[cfg(not(feature = "decimal"))]
+pub type KDecimal = String;
+
+/// Type of the prices, volumes and amounts in output structs. Kraken sends these as strings to
+/// avoid any loss of precision, which are parsed into exact [rust_decimal::Decimal] values since
+/// the `decimal` feature is enabled
+#[cfg(feature = "decimal")]
+pub type KDecimal = rust_decimal::Decimal;
+
+/// Type of unix timestamps with fractional seconds in output structs, i.e.
+/// [KOOrderInfo::opentm][private::KOOrderInfo::opentm]. Kept as seconds since epoch by default.
+/// With the `timestamps` feature enabled they are parsed into `chrono::DateTime<Utc>` values
+/// with microsecond precision instead
+#[cfg(not(feature = "timestamps"))]
+pub type KTimestamp = f64;
+
+/// Type of unix timestamps with fractional seconds in output structs, i.e.
+/// [KOOrderInfo::opentm][private::KOOrderInfo::opentm]. Parsed into [chrono::DateTime] values
+/// with microsecond precision since the `timestamps` feature is enabled
+#[cfg(feature = "timestamps")]
+pub type KTimestamp = chrono::DateTime<chrono::Utc>;
+
+/// Type of unix timestamps in whole seconds in output structs, i.e.
+/// [KOOHLCData::timestamp][public::ohlc::KOOHLCData::timestamp]. Kept as seconds since epoch by
+/// default. With the `timestamps` feature enabled they are parsed into `chrono::DateTime<Utc>`
+/// values instead
+#[cfg(not(feature = "timestamps"))]
+pub type KUnixTime = i64;
+
+/// Type of unix timestamps in whole seconds in output structs, i.e.
+/// [KOOHLCData::timestamp][public::ohlc::KOOHLCData::timestamp]. Parsed into [chrono::DateTime]
+/// values since the `timestamps` feature is enabled
+#[cfg(feature = "timestamps")]
+pub type KUnixTime = chrono::DateTime<chrono::Utc>;
+
+/// Type of RFC 3339 date-times in output structs, i.e.
+/// [KOSystemStatus::timestamp][public::system_status::KOSystemStatus::timestamp]. Kept as sent by
+/// Kraken by default. With the `timestamps` feature enabled they are parsed into
+/// `chrono::DateTime<Utc>` values instead
+#[cfg(not(feature = "timestamps"))]
+pub type KDateTime = String;
+
+/// Type of RFC 3339 date-times in output structs, i.e.
+/// [KOSystemStatus::timestamp][public::system_status::KOSystemStatus::timestamp]. Parsed into
+/// [chrono::DateTime] values since the `timestamps` feature is enabled
+#[cfg(feature = "timestamps")]
+pub type KDateTime = chrono::DateTime<chrono::Utc>;
+
+// Unix timestamp sent either as a number by the REST API or as a string by the websocket API,
+// i.e. "1560516023.070651"
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTimestamp {
+    Number(f64),
+    Text(String),
+}
+
+impl RawTimestamp {
+    fn into_timestamp<E>(self) -> Result<KTimestamp, E>
+    where
+        E: serde::de::Error,
+    {
+        let secs = match self {
+            RawTimestamp::Number(secs) => secs,
+            RawTimestamp::Text(buf) => match buf.parse::<f64>() {
+                Ok(secs) => secs,
+                #[cfg(feature = "timestamps")]
+                Err(_) => return buf.parse::<KTimestamp>().map_err(E::custom),
+                #[cfg(not(feature = "timestamps"))]
+                Err(err) => return Err(E::custom(err)),
+            },
+        };
+        timestamp_from_secs(secs).ok_or_else(|| E::custom("timestamp out of range"))
+    }
+}
+
+pub(crate) fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<KTimestamp, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    RawTimestamp::deserialize(deserializer)?.into_timestamp()
+}
+
+pub(crate) fn deserialize_opt_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<KTimestamp>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    match Option::<RawTimestamp>::deserialize(deserializer)? {
+        Some(raw) => raw.into_timestamp().map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(not(feature = "timestamps"))]
+pub(crate) fn deserialize_seconds<'de, D>(deserializer: D) -> Result<KUnixTime, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    i64::deserialize(deserializer)
+}
+
+// Unix time sent as a number by Kraken, or the RFC 3339 string a KUnixTime serializes to
+#[cfg(feature = "timestamps")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSeconds {
+    Number(i64),
+    Text(String),
+}
+
+#[cfg(feature = "timestamps")]
+pub(crate) fn deserialize_seconds<'de, D>(deserializer: D) -> Result<KUnixTime, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    match RawSeconds::deserialize(deserializer)? {
+        RawSeconds::Number(secs) => chrono::DateTime::from_timestamp(secs, 0)
+            .ok_or_else(|| serde::de::Error::custom("timestamp out of range")),
+        RawSeconds::Text(buf) => buf.parse::<KUnixTime>().map_err(serde::de::Error::custom),
+    }
+}
+
+// Seconds since epoch as a timestamp, rounded to microseconds
+#[cfg(not(feature = "timestamps"))]
+pub(crate) fn timestamp_from_secs(secs: f64) -> Option<KTimestamp> {
+    Some(secs)
+}
+
+// Seconds since epoch as a timestamp, rounded to microseconds
+#[cfg(feature = "timestamps")]
+pub(crate) fn timestamp_from_secs(secs: f64) -> Option<KTimestamp> {
+    chrono::DateTime::from_timestamp_micros((secs * 1_000_000.0).round() as i64)
+}
+
+// Seconds since epoch of a timestamp
+#[cfg(not(feature = "timestamps"))]
+pub(crate) fn timestamp_secs(timestamp: &KTimestamp) -> f64 {
+    *timestamp
+}
+
+// Seconds since epoch of a timestamp
+#[cfg(feature = "timestamps")]
+pub(crate) fn timestamp_secs(timestamp: &KTimestamp) -> f64 {
+    timestamp.timestamp_micros() as f64 / 1_000_000.0
+}
+
+// Seconds since epoch of a unix time
+#[cfg(not(feature = "timestamps"))]
+pub(crate) fn unix_time_secs(time: &KUnixTime) -> i64 {
+    *time
+}
+
+// Seconds since epoch of a unix time
+#[cfg(feature = "timestamps")]
+pub(crate) fn unix_time_secs(time: &KUnixTime) -> i64 {
+    time.timestamp()
+}
+
+/// Format `value` with at most `decimals` decimal places, truncating any further digits, i.e. to
+/// the `pair_decimals` or `lot_decimals` of a [KOAssetPair][public::asset_pairs::KOAssetPair].
+/// Requires the `decimal` feature
+#[cfg(feature = "decimal")]
+pub fn format_decimal(value: &rust_decimal::Decimal, decimals: u32) -> String {
+    value
+        .round_dp_with_strategy(decimals, rust_decimal::RoundingStrategy::ToZero)
+        .to_string()
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub(crate) struct KResult<T> {
     /// Generic payload type. T will be some type prefixed with KO
@@ -24,6 +198,7 @@ pub(crate) struct KResult<T> {
     pub error: Vec<String>,
 }
 
+#[derive(Clone)]
 pub(crate) enum MethodType {
     Private,
     Public,
@@ -39,8 +214,8 @@ impl fmt::Display for MethodType {
 }
 
 /// System status | See [KISystemStatus][public::system_status::KISystemStatus]
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "lowercase")]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum SystemStatus {
     /// Operational, full trading available
     Online,
@@ -51,6 +226,7 @@ pub enum SystemStatus {
     /// Existing orders are cancelable, and only new limit orders can be submitted
     LimitOnly,
     /// System is offline for maintenance
+    #[serde(alias = "maintenance")]
     Offline,
 }
 
@@ -76,6 +252,23 @@ pub enum OHLCInterval {
     TwentyoneSixty,
 }
 
+impl OHLCInterval {
+    /// Length of the interval in minutes
+    pub fn minutes(&self) -> u32 {
+        match self {
+            OHLCInterval::One => 1,
+            OHLCInterval::Five => 5,
+            OHLCInterval::Fifteen => 15,
+            OHLCInterval::Thirty => 30,
+            OHLCInterval::Sixty => 60,
+            OHLCInterval::TwoForty => 240,
+            OHLCInterval::FourteenForty => 1440,
+            OHLCInterval::TenEighty => 10080,
+            OHLCInterval::TwentyoneSixty => 21600,
+        }
+    }
+}
+
 impl fmt::Display for OHLCInterval {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -179,6 +372,10 @@ impl fmt::Display for TradeType {
 /// # will either add or subtract the amount to the current offered price, depending on
 /// the type and order type used. Relative prices can be suffixed with a % to signify
 /// the relative amount as a percentage of the offered price.
+///
+/// With the `decimal` feature enabled, order types with absolute prices can also be constructed
+/// from exact decimals, i.e. with [limit_decimal()][OrderType::limit_decimal]. Relative prices
+/// and trailing offsets are only accepted as strings since they carry a prefix or suffix
 pub enum OrderType {
     /// Market order type with market price inferred
     Market,
@@ -192,16 +389,28 @@ pub enum OrderType {
     StopLossLimit(String, String),
     /// Take Profit Limit order with the take profit trigger price and the triggered limit price
     TakeProfitLimit(String, String),
+    /// Trailing Stop order with the trailing offset from the best price. The offset is always
+    /// relative and its direction follows from the trade type, so Kraken only accepts a + prefix.
+    /// One is added if none is given and a - prefix is replaced
+    TrailingStop(String),
+    /// Trailing Stop Limit order with the trailing trigger offset and the offset of the limit
+    /// price from the trigger price. Both offsets are prefixed like the offset of
+    /// [OrderType::TrailingStop]
+    TrailingStopLimit(String, String),
+    /// Iceberg order with the limit price and the visible (display) volume of the order. The
+    /// display volume must be at least 1/15 of the total order volume
+    Iceberg(String, String),
     SettlePosition,
 }
 
 use OrderType::{
-    Limit, Market, SettlePosition, StopLoss, StopLossLimit, TakeProfit, TakeProfitLimit,
+    Iceberg, Limit, Market, SettlePosition, StopLoss, StopLossLimit, TakeProfit, TakeProfitLimit,
+    TrailingStop, TrailingStopLimit,
 };
 impl OrderType {
     // FIXME: Avoid the empty strings using options and fix the pattern matching in
     // percent_encode()
-    fn elide(&self) -> (Option<String>, Option<String>) {
+    pub(crate) fn elide(&self) -> (Option<String>, Option<String>) {
         match self {
             Market => (None, None),
             Limit(price1) => (Some(price1.to_string()), None),
@@ -209,6 +418,11 @@ impl OrderType {
             TakeProfit(price1) => (Some(price1.to_string()), None),
             StopLossLimit(price1, price2) => (Some(price1.to_string()), Some(price2.to_string())),
             TakeProfitLimit(price1, price2) => (Some(price1.to_string()), Some(price2.to_string())),
+            TrailingStop(offset) => (Some(relative(offset)), None),
+            TrailingStopLimit(offset1, offset2) => {
+                (Some(relative(offset1)), Some(relative(offset2)))
+            }
+            Iceberg(price1, _) => (Some(price1.to_string()), None),
             SettlePosition => (None, None),
         }
     }
@@ -216,26 +430,9 @@ impl OrderType {
     pub(crate) fn percent_encode(&self) -> (Option<String>, Option<String>) {
         match self.elide() {
             (Some(price1), Some(price2)) => {
-                let encoded_price1 = price1
-                    .replace("+", "%2B")
-                    .replace("#", "%23")
-                    .replace("%", "%25");
-
-                let encoded_price2 = price2
-                    .replace("+", "%2B")
-                    .replace("#", "%23")
-                    .replace("%", "%25");
-
-                (Some(encoded_price1), Some(encoded_price2))
-            }
-            (Some(price1), None) => {
-                let encoded_price1 = price1
-                    .replace("+", "%2B")
-                    .replace("#", "%23")
-                    .replace("%", "%25");
-
-                (Some(encoded_price1), None)
+                (Some(encode_price(&price1)), Some(encode_price(&price2)))
             }
+            (Some(price1), None) => (Some(encode_price(&price1)), None),
             (None, Some(_)) => {
                 unreachable!()
             }
@@ -256,6 +453,121 @@ impl OrderType {
             (_, None) => None,
         }
     }
+
+    pub(crate) fn display_volume(&self) -> Option<String> {
+        match self {
+            Iceberg(_, volume) => Some(volume.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Limit order with an exact decimal limit price. Requires the `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn limit_decimal(price: rust_decimal::Decimal) -> Self {
+        Limit(price.to_string())
+    }
+
+    /// Stop Loss order with an exact decimal stop loss price. Requires the `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn stop_loss_decimal(price: rust_decimal::Decimal) -> Self {
+        StopLoss(price.to_string())
+    }
+
+    /// Take Profit order with an exact decimal take profit price. Requires the `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn take_profit_decimal(price: rust_decimal::Decimal) -> Self {
+        TakeProfit(price.to_string())
+    }
+
+    /// Stop Loss Limit order with exact decimal trigger and limit prices. Requires the `decimal`
+    /// feature
+    #[cfg(feature = "decimal")]
+    pub fn stop_loss_limit_decimal(
+        price1: rust_decimal::Decimal,
+        price2: rust_decimal::Decimal,
+    ) -> Self {
+        StopLossLimit(price1.to_string(), price2.to_string())
+    }
+
+    /// Take Profit Limit order with exact decimal trigger and limit prices. Requires the
+    /// `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn take_profit_limit_decimal(
+        price1: rust_decimal::Decimal,
+        price2: rust_decimal::Decimal,
+    ) -> Self {
+        TakeProfitLimit(price1.to_string(), price2.to_string())
+    }
+
+    /// Iceberg order with an exact decimal limit price and display volume. Requires the
+    /// `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn iceberg_decimal(price: rust_decimal::Decimal, volume: rust_decimal::Decimal) -> Self {
+        Iceberg(price.to_string(), volume.to_string())
+    }
+
+    /// Round every price of this order type to the asset pair's `pair_decimals`, and the display
+    /// volume of iceberg orders to its `lot_decimals`, truncating any further digits. Relative
+    /// prefixes are kept and percentages are left unchanged. Requires the `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn with_precision(self, pair: &public::asset_pairs::KOAssetPair) -> Self {
+        let price = |price: String| round_price(&price, pair.pair_decimals);
+        match self {
+            Limit(price1) => Limit(price(price1)),
+            StopLoss(price1) => StopLoss(price(price1)),
+            TakeProfit(price1) => TakeProfit(price(price1)),
+            StopLossLimit(price1, price2) => StopLossLimit(price(price1), price(price2)),
+            TakeProfitLimit(price1, price2) => TakeProfitLimit(price(price1), price(price2)),
+            TrailingStop(offset) => TrailingStop(price(offset)),
+            TrailingStopLimit(offset1, offset2) => {
+                TrailingStopLimit(price(offset1), price(offset2))
+            }
+            Iceberg(price1, volume) => {
+                Iceberg(price(price1), round_price(&volume, pair.lot_decimals))
+            }
+            ordertype => ordertype,
+        }
+    }
+}
+
+// Round a price that may carry a relative prefix (+, -, #) to `decimals` decimal places
+#[cfg(feature = "decimal")]
+fn round_price(price: &str, decimals: u32) -> String {
+    if price.ends_with('%') {
+        return price.to_string();
+    }
+    let (prefix, value) = match price.chars().next() {
+        Some(sign @ '+') | Some(sign @ '-') | Some(sign @ '#') => price.split_at(sign.len_utf8()),
+        _ => ("", price),
+    };
+    match value.parse::<rust_decimal::Decimal>() {
+        Ok(value) => format!("{}{}", prefix, format_decimal(&value, decimals)),
+        Err(_) => price.to_string(),
+    }
+}
+
+// Trailing stop offsets are always relative to the best price. Kraken only accepts the + prefix
+fn relative(offset: &str) -> String {
+    let offset = offset.strip_prefix(&['+', '-'][..]).unwrap_or(offset);
+    format!("+{}", offset)
+}
+
+// Percent encode the relative price prefixes (+, #) and percentage suffix of a price so it
+// survives being sent as a url encoded parameter. % goes first so the other escapes aren't
+// encoded twice
+pub(crate) fn encode_price(price: &str) -> String {
+    price
+        .replace("%", "%25")
+        .replace("+", "%2B")
+        .replace("#", "%23")
+}
+
+// Undo encode_price() and the percent encoding of the relative time prefix
+pub(crate) fn decode_param(value: &str) -> String {
+    value
+        .replace("%2B", "+")
+        .replace("%23", "#")
+        .replace("%25", "%")
 }
 
 impl fmt::Display for OrderType {
@@ -267,11 +579,71 @@ impl fmt::Display for OrderType {
             OrderType::TakeProfit(_) => write!(f, "take-profit"),
             OrderType::StopLossLimit(_, _) => write!(f, "stop-loss-limit"),
             OrderType::TakeProfitLimit(_, _) => write!(f, "take-profit-limit"),
+            OrderType::TrailingStop(_) => write!(f, "trailing-stop"),
+            OrderType::TrailingStopLimit(_, _) => write!(f, "trailing-stop-limit"),
+            OrderType::Iceberg(_, _) => write!(f, "iceberg"),
             OrderType::SettlePosition => write!(f, "settle-position"),
         }
     }
 }
 
+/// Price used to trigger conditional orders | See [KIAddOrder][private::add_order::KIAddOrder]
+pub enum OrderTrigger {
+    /// Last traded price in the order book
+    Last,
+    /// Index price for the broader market
+    Index,
+}
+
+impl fmt::Display for OrderTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderTrigger::Last => write!(f, "last"),
+            OrderTrigger::Index => write!(f, "index"),
+        }
+    }
+}
+
+/// Time in force of an order | See [KIAddOrder][private::add_order::KIAddOrder]
+pub enum TimeInForce {
+    /// Good till cancelled
+    GTC,
+    /// Immediate or cancel
+    IOC,
+    /// Good till date. Requires an expire time to be set on the order
+    GTD,
+}
+
+impl fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeInForce::GTC => write!(f, "GTC"),
+            TimeInForce::IOC => write!(f, "IOC"),
+            TimeInForce::GTD => write!(f, "GTD"),
+        }
+    }
+}
+
+/// Self trade prevention behaviour | See [KIAddOrder][private::add_order::KIAddOrder]
+pub enum SelfTradePrevention {
+    /// Cancel the arriving order
+    CancelNewest,
+    /// Cancel the resting order
+    CancelOldest,
+    /// Cancel both the arriving and the resting order
+    CancelBoth,
+}
+
+impl fmt::Display for SelfTradePrevention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfTradePrevention::CancelNewest => write!(f, "cancel-newest"),
+            SelfTradePrevention::CancelOldest => write!(f, "cancel-oldest"),
+            SelfTradePrevention::CancelBoth => write!(f, "cancel-both"),
+        }
+    }
+}
+
 /// Add order flags | See [KIAddOrder][private::add_order::KIAddOrder]
 pub enum OrderFlags {
     /// Prefer fee in base currency
@@ -295,6 +667,7 @@ impl fmt::Display for OrderFlags {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct EndpointInfo {
     methodtype: MethodType,
     endpoint: String,
@@ -314,6 +687,7 @@ impl EndpointInfo {
 /// KrakenInput can't be constructed directly. An instance is created by calling finish() or
 /// finish_clone() on an input builder type (types prefixed with "KI"). See the [Input] trait for
 /// KrakenInput builder types
+#[derive(Clone)]
 pub struct KrakenInput {
     info: EndpointInfo,
     params: Option<IndexMap<String, String>>,
@@ -326,10 +700,101 @@ impl KrakenInput {
 
     pub(crate) fn params(&self) -> Option<&IndexMap<String, String>> {
         match &self.params {
-            Some(params) => Some(&params),
+            Some(params) => Some(params),
             None => None,
         }
     }
+
+    // Replace the nonce of a private request right before it is signed
+    pub(crate) fn set_nonce(&mut self, nonce: u64) {
+        if let Some(params) = self.params.as_mut() {
+            params.insert(String::from("nonce"), nonce.to_string());
+        }
+    }
+
+    // Request body of the endpoints taking arrays of orders, which Kraken only accepts as JSON
+    // instead of url encoded params. None for every other endpoint
+    pub(crate) fn json_body(&self) -> Option<String> {
+        match self.info.endpoint.as_str() {
+            "AddOrderBatch" => Some(json_params(self.params.as_ref()?).to_string()),
+            // User reference IDs are listed apart from the txids so they are sent as numbers.
+            // Kraken expects both in the orders list
+            "CancelOrderBatch" => {
+                let mut body = json_params(self.params.as_ref()?);
+                let body_map = body.as_object_mut()?;
+                if let Some(Value::Array(userrefs)) = body_map.remove("userrefs") {
+                    let orders = body_map
+                        .entry("orders")
+                        .or_insert_with(|| Value::Array(Vec::new()));
+                    if let Some(orders) = orders.as_array_mut() {
+                        orders.extend(userrefs);
+                    }
+                }
+                Some(body.to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+// Nest the url encoded params into a JSON object, i.e. orders[0][close][ordertype] becomes
+// {"orders": [{"close": {"ordertype": ..}}]}. Nonces and user reference IDs are sent as numbers,
+// flags as booleans and every other value as a string. List entries are typed by the name of
+// their list
+fn json_params(params: &IndexMap<String, String>) -> Value {
+    let mut body = Value::Object(Map::new());
+    for (key, value) in params {
+        let key = key.replace("%5B", "[").replace("%5D", "]");
+        let path: Vec<&str> = key
+            .split('[')
+            .map(|segment| segment.trim_end_matches(']'))
+            .collect();
+        let node = path
+            .iter()
+            .fold(&mut body, |node, segment| json_child(node, segment));
+
+        let name = path
+            .iter()
+            .rev()
+            .find(|segment| segment.parse::<usize>().is_err())
+            .unwrap();
+        let value = decode_param(value);
+        *node = match *name {
+            "nonce" | "userref" | "userrefs" => value
+                .parse::<i64>()
+                .map_or(Value::String(value), Value::from),
+            "validate" | "reduce_only" => value
+                .parse::<bool>()
+                .map_or(Value::String(value), Value::from),
+            _ => Value::String(value),
+        };
+    }
+    body
+}
+
+// Child of `node` at `segment`, an array index if it is numeric or an object key otherwise
+fn json_child<'a>(node: &'a mut Value, segment: &str) -> &'a mut Value {
+    match segment.parse::<usize>() {
+        Ok(index) => {
+            if !node.is_array() {
+                *node = Value::Array(Vec::new());
+            }
+            let array = node.as_array_mut().unwrap();
+            if array.len() <= index {
+                array.resize(index + 1, Value::Null);
+            }
+            &mut array[index]
+        }
+        Err(_) => {
+            if !node.is_object() {
+                *node = Value::Object(Map::new());
+            }
+            node.as_object_mut()
+                .unwrap()
+                .entry(segment)
+                .or_insert(Value::Null)
+        }
+    }
 }
 
 /// Trait used by input builder types to construct a [KrakenInput]. All input builder
@@ -344,6 +809,14 @@ pub trait Input {
 /// method expects the correct output types
 pub trait Output {}
 
+/// Trait for output types that are returned from Kraken as a file rather than JSON. Used by
+/// [KrakenClient][super::client::KrakenClient]'s
+/// [request_binary][super::client::KrakenClient::request_binary] method
+pub trait BinaryOutput {
+    /// Construct the output from the raw response body
+    fn from_bytes(bytes: Vec<u8>) -> Self;
+}
+
 // This trait allows us to get a mutable reference to the input data
 pub(crate) trait MutateInput {
     // Get mutable access to the input parameters of the implementing type
@@ -388,7 +861,7 @@ pub(crate) trait InputListItem: IntoInputList {
                     return;
                 }
 
-                *list = format!("{},{}", list, item.to_string());
+                *list = format!("{},{}", list, item);
             }
             None => {
                 self.list_mut().insert(listname, item.to_string());
@@ -474,3 +947,114 @@ where
         None => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_relative_prices() {
+        let order = OrderType::StopLossLimit(String::from("#5%"), String::from("+10"));
+        assert_eq!(
+            order.percent_encode(),
+            (Some(String::from("%235%25")), Some(String::from("%2B10")))
+        );
+
+        let order = OrderType::TrailingStopLimit(String::from("1.5%"), String::from("-20"));
+        assert_eq!(
+            order.percent_encode(),
+            (Some(String::from("%2B1.5%25")), Some(String::from("%2B20")))
+        );
+
+        let order = OrderType::Iceberg(String::from("30000"), String::from("0.1"));
+        assert_eq!(order.price1(), Some(String::from("30000")));
+        assert_eq!(order.display_volume(), Some(String::from("0.1")));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn round_to_pair_precision() {
+        use crate::api::private::add_order::KIAddOrder;
+        use asset::{KAsset, KAssetPair};
+
+        let info: public::asset_pairs::KOAssetPair = serde_json::from_value(serde_json::json!({
+            "aclass_base": "currency", "aclass_quote": "currency", "altname": "XBTUSD",
+            "base": "XXBT", "fee_volume_currency": "ZUSD", "fees": [[0, 0.26]],
+            "leverage_buy": [2, 3], "leverage_sell": [2, 3], "lot": "unit", "lot_decimals": 8,
+            "lot_multiplier": 1, "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001",
+            "pair_decimals": 1, "quote": "ZUSD"
+        }))
+        .unwrap();
+
+        let order = OrderType::StopLossLimit(String::from("#30000.123"), String::from("-5.99"))
+            .with_precision(&info);
+        assert_eq!(
+            order.elide(),
+            (Some(String::from("#30000.1")), Some(String::from("-5.9")))
+        );
+        let order = OrderType::TrailingStop(String::from("1.25%")).with_precision(&info);
+        assert_eq!(order.elide(), (Some(String::from("+1.25%")), None));
+        let order = OrderType::iceberg_decimal(
+            "30000.123".parse().unwrap(),
+            "0.123456789".parse().unwrap(),
+        )
+        .with_precision(&info);
+        assert_eq!(order.elide(), (Some(String::from("30000.1")), None));
+        assert_eq!(order.display_volume(), Some(String::from("0.12345678")));
+
+        let volume = "0.123456789".parse().unwrap();
+        let input = KIAddOrder::build_decimal(
+            KAssetPair(KAsset::XBT, KAsset::USD),
+            TradeType::Buy,
+            OrderType::Limit(String::from("30000.05")),
+            volume,
+            &info,
+        )
+        .finish();
+        let params = input.params().unwrap();
+        assert_eq!(params.get("price").unwrap(), "30000.0");
+        assert_eq!(params.get("volume").unwrap(), "0.12345678");
+    }
+
+    #[cfg(feature = "timestamps")]
+    #[test]
+    fn parse_timestamps() {
+        use chrono::{TimeZone, Utc};
+
+        let ledger: private::KOLedgerInfo = serde_json::from_value(serde_json::json!({
+            "refid": "TJKLXX-PNPR6-MZMHBN", "time": 1616336594.1234, "type": "trade",
+            "aclass": "currency", "asset": "XXBT", "amount": "0.1", "fee": "0", "balance": "1"
+        }))
+        .unwrap();
+        assert_eq!(
+            ledger.time,
+            Utc.timestamp_opt(1616336594, 123_400_000).unwrap()
+        );
+
+        // Serialized outputs deserialize again
+        let candle: public::ohlc::KOOHLCData = serde_json::from_value(serde_json::json!([
+            1616336594, "1", "1", "1", "1", "1", "0.1", 1
+        ]))
+        .unwrap();
+        let candle: public::ohlc::KOOHLCData =
+            serde_json::from_value(serde_json::to_value(&candle).unwrap()).unwrap();
+        assert_eq!(candle.timestamp, Utc.timestamp_opt(1616336594, 0).unwrap());
+        let ledger: private::KOLedgerInfo =
+            serde_json::from_value(serde_json::to_value(&ledger).unwrap()).unwrap();
+        assert_eq!(
+            ledger.time,
+            Utc.timestamp_opt(1616336594, 123_400_000).unwrap()
+        );
+
+        let status: public::system_status::KOSystemStatus = serde_json::from_value(
+            serde_json::json!({ "status": "online", "timestamp": "2021-03-21T14:23:14Z" }),
+        )
+        .unwrap();
+        assert_eq!(status.timestamp, Utc.timestamp_opt(1616336594, 0).unwrap());
+
+        let input = private::closed_orders::KIClosedOrders::build()
+            .starting_datetime(status.timestamp)
+            .finish();
+        assert_eq!(input.params().unwrap().get("start").unwrap(), "1616336594");
+    }
+}
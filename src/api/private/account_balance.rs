@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use crate::auth::KrakenAuth;
 // Structs/Enums
-use super::{EndpointInfo, KrakenInput, MethodType};
+use super::{EndpointInfo, KDecimal, KrakenInput, MethodType};
 use crate::api::asset::KAsset;
 
 // Traits
@@ -69,7 +69,7 @@ impl UpdateInput for KIAccountBalance {}
 pub struct KOAccountBalance {
     /// Map with the asset as the key and the asset's current balance as the value
     #[serde(flatten)]
-    pub balances: HashMap<KAsset, String>,
+    pub balances: HashMap<KAsset, KDecimal>,
 }
 
 impl Output for KOAccountBalance {}
@@ -0,0 +1,100 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Account Transfer endpoint
+pub struct KIAccountTransfer {
+    params: IndexMap<String, String>,
+}
+
+impl KIAccountTransfer {
+    /// Constructor returning a [KrakenInput] builder for the account transfer endpoint.
+    /// Transfers `amount` of `asset` between the master account and a subaccount. `from` and `to`
+    /// are the IIBANs of the source and destination accounts. Must be sent with the credentials of
+    /// the master account
+    pub fn build(asset: KAsset, amount: f64, from: String, to: String) -> KIAccountTransfer {
+        let transfer = KIAccountTransfer {
+            params: IndexMap::new(),
+        };
+        transfer
+            .with_asset(asset)
+            .with_amount(amount)
+            .with_source(from)
+            .with_destination(to)
+    }
+
+    /// Update the asset to transfer
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the amount to transfer
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    /// Update the IIBAN of the account to transfer from
+    pub fn with_source(self, from: String) -> Self {
+        self.update_input("from", from)
+    }
+
+    /// Update the IIBAN of the account to transfer to
+    pub fn with_destination(self, to: String) -> Self {
+        self.update_input("to", to)
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIAccountTransfer {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIAccountTransfer {}
+
+impl Input for KIAccountTransfer {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("AccountTransfer"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("AccountTransfer"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Account Transfer endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOAccountTransfer {
+    /// ID of the transfer
+    pub transfer_id: String,
+    /// Status of the transfer (i.e. complete)
+    pub status: String,
+}
+
+impl Output for KOAccountTransfer {}
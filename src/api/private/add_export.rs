@@ -0,0 +1,154 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Type of data export report
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportReport {
+    /// Trades history report
+    Trades,
+    /// Ledgers report
+    Ledgers,
+}
+
+impl Display for ExportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportReport::Trades => write!(f, "trades"),
+            ExportReport::Ledgers => write!(f, "ledgers"),
+        }
+    }
+}
+
+/// File format of data export report
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ExportFormat {
+    /// Comma separated values
+    CSV,
+    /// Tab separated values
+    TSV,
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::CSV => write!(f, "CSV"),
+            ExportFormat::TSV => write!(f, "TSV"),
+        }
+    }
+}
+
+/// Request builder for the Request Export Report endpoint
+pub struct KIAddExport {
+    params: IndexMap<String, String>,
+}
+
+impl KIAddExport {
+    /// Constructor returning a [KrakenInput] builder for the request export report endpoint.
+    /// description is a name for the report used to find it with the
+    /// [Get Export Report Status][super::export_status::KIExportStatus] endpoint
+    pub fn build(report: ExportReport, description: String) -> KIAddExport {
+        let export = KIAddExport {
+            params: IndexMap::new(),
+        };
+        export.with_report(report).with_description(description)
+    }
+
+    /// Update the type of report to export
+    pub fn with_report(self, report: ExportReport) -> Self {
+        self.update_input("report", report.to_string())
+    }
+
+    /// Update the description of the report
+    pub fn with_description(self, description: String) -> Self {
+        self.update_input("description", description)
+    }
+
+    /// File format of the report. Defaults to CSV
+    pub fn with_format(self, format: ExportFormat) -> Self {
+        self.update_input("format", format.to_string())
+    }
+
+    /// Comma delimited list of fields to include in the report. Defaults to all fields
+    pub fn with_fields(self, fields: String) -> Self {
+        self.update_input("fields", fields)
+    }
+
+    /// Starting Unix timestamp of the report data. Defaults to one year before now
+    pub fn starting_timestamp(self, timestamp: u64) -> Self {
+        self.update_input("starttm", timestamp.to_string())
+    }
+
+    /// Starting time of the report data. Defaults to one year before now. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn starting_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("starttm", time.timestamp().to_string())
+    }
+
+    /// Ending Unix timestamp of the report data. Defaults to now
+    pub fn ending_timestamp(self, timestamp: u64) -> Self {
+        self.update_input("endtm", timestamp.to_string())
+    }
+
+    /// Ending time of the report data. Defaults to now. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn ending_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("endtm", time.timestamp().to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIAddExport {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIAddExport {}
+
+impl Input for KIAddExport {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("AddExport"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("AddExport"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Request Export Report endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOAddExport {
+    /// Report ID
+    pub id: String,
+}
+
+impl Output for KOAddExport {}
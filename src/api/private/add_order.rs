@@ -2,9 +2,16 @@ use indexmap::map::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
+use crate::api::public::asset_pairs::KOAssetPair;
+use crate::api::public::system_status::KOSystemStatus;
+use crate::api::SystemStatus;
 use crate::auth::KrakenAuth;
+use crate::error::{KError, KrakenErrors};
 // Structs/Enums
-use super::{EndpointInfo, KAssetPair, KrakenInput, MethodType, OrderFlags, OrderType, TradeType};
+use super::{
+    EndpointInfo, KAssetPair, KrakenInput, MethodType, OrderFlags, OrderTrigger, OrderType,
+    SelfTradePrevention, TimeInForce, TradeType,
+};
 
 // Traits
 use super::{Input, MutateInput, Output, UpdateInput};
@@ -32,6 +39,103 @@ impl Display for Leverage {
     }
 }
 
+/// Reason an order failed local validation | See [KIAddOrder::check()]
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderViolation {
+    /// Kraken doesn't accept this order in its current [SystemStatus]
+    SystemStatus(SystemStatus),
+    /// Order volume is below the asset pair's `ordermin`
+    BelowMinimum {
+        /// Order volume
+        volume: String,
+        /// Minimum order volume of the pair
+        ordermin: String,
+    },
+    /// Order or display volume has more decimal places than the asset pair's `lot_decimals`
+    VolumePrecision {
+        /// Offending volume
+        volume: String,
+        /// Decimal places allowed for volumes of the pair
+        lot_decimals: u32,
+    },
+    /// Price has more decimal places than the asset pair's `pair_decimals`
+    PricePrecision {
+        /// Offending price, without any relative prefix
+        price: String,
+        /// Decimal places allowed for prices of the pair
+        pair_decimals: u32,
+    },
+    /// Requested leverage isn't offered for this side of the asset pair
+    LeverageUnavailable {
+        /// Requested leverage
+        leverage: u32,
+        /// Leverage amounts offered for this side of the pair
+        available: Vec<u32>,
+    },
+    /// Order type can't be used for the closing order
+    ClosingOrderType {
+        /// Order type of the closing order
+        ordertype: String,
+    },
+}
+
+impl Display for OrderViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderViolation::SystemStatus(SystemStatus::PostOnly) => {
+                write!(
+                    f,
+                    "only post-only limit orders are accepted in post only mode"
+                )
+            }
+            OrderViolation::SystemStatus(SystemStatus::LimitOnly) => {
+                write!(f, "only limit orders are accepted in limit only mode")
+            }
+            OrderViolation::SystemStatus(status) => {
+                write!(
+                    f,
+                    "no new orders are accepted while the system status is {:?}",
+                    status
+                )
+            }
+            OrderViolation::BelowMinimum { volume, ordermin } => {
+                write!(
+                    f,
+                    "volume {} is below the minimum order volume {}",
+                    volume, ordermin
+                )
+            }
+            OrderViolation::VolumePrecision {
+                volume,
+                lot_decimals,
+            } => write!(
+                f,
+                "volume {} has more than {} decimal places",
+                volume, lot_decimals
+            ),
+            OrderViolation::PricePrecision {
+                price,
+                pair_decimals,
+            } => write!(
+                f,
+                "price {} has more than {} decimal places",
+                price, pair_decimals
+            ),
+            OrderViolation::LeverageUnavailable {
+                leverage,
+                available,
+            } => write!(
+                f,
+                "leverage {} is not offered, available leverage: {:?}",
+                leverage, available
+            ),
+            OrderViolation::ClosingOrderType { ordertype } => {
+                write!(f, "{} orders can't be used as closing orders", ordertype)
+            }
+        }
+    }
+}
+
 /// Request builder for the Add Standard Order endpoint
 pub struct KIAddOrder {
     params: IndexMap<String, String>,
@@ -59,9 +163,41 @@ impl KIAddOrder {
             .with_order_type_ref(&ordertype)
             .with_price1(&ordertype)
             .with_price2(&ordertype)
+            .with_display_volume(&ordertype)
             .with_volume(volume)
     }
 
+    /// Constructor taking an exact decimal volume. Prices and volume are rounded to the
+    /// `pair_decimals` and `lot_decimals` of `info`, the asset pair info of `pair`. Requires the
+    /// `decimal` feature
+    ///
+    /// * `pair` - asset pair for order
+    /// * `tradetype` - [TradeType]
+    /// * `ordertype` - [OrderType]
+    /// * `volume` - order volume in lots
+    /// * `info` - [KOAssetPair] info of `pair`
+    #[cfg(feature = "decimal")]
+    pub fn build_decimal(
+        pair: KAssetPair,
+        tradetype: TradeType,
+        ordertype: OrderType,
+        volume: rust_decimal::Decimal,
+        info: &KOAssetPair,
+    ) -> Self {
+        let ordertype = ordertype.with_precision(info);
+        let new = KIAddOrder {
+            params: IndexMap::new(),
+        };
+
+        new.with_pair(pair)
+            .with_transaction_type(tradetype)
+            .with_order_type_ref(&ordertype)
+            .with_price1(&ordertype)
+            .with_price2(&ordertype)
+            .with_display_volume(&ordertype)
+            .with_decimal_volume(volume, info.lot_decimals)
+    }
+
     /// Update the asset pair for this order. Useful for templating
     pub fn with_pair(self, pair: KAssetPair) -> Self {
         self.update_input("pair", pair.to_string())
@@ -96,15 +232,29 @@ impl KIAddOrder {
         }
     }
 
+    fn with_display_volume(self, ordertype: &OrderType) -> Self {
+        match ordertype.display_volume() {
+            Some(volume) => self.update_input("displayvol", volume),
+            None => self,
+        }
+    }
+
     /// Update the order volume in lots
     pub fn with_volume(self, volume: f64) -> Self {
         self.update_input("volume", volume.to_string())
     }
 
+    /// Update the order volume in lots with an exact decimal volume, truncated to `lot_decimals`
+    /// decimal places. Requires the `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn with_decimal_volume(self, volume: rust_decimal::Decimal, lot_decimals: u32) -> Self {
+        self.update_input("volume", crate::api::format_decimal(&volume, lot_decimals))
+    }
+
     /// Amount of leverage for this order. Subject to [margin trading
     /// restrictions](https://support.kraken.com/hc/en-us/articles/227876608)
     pub fn with_leverage(self, leverage: Leverage) -> Self {
-        self.update_input("leverage", format!("{}:{}", leverage.to_string(), 1u8))
+        self.update_input("leverage", format!("{}:{}", leverage, 1u8))
     }
 
     /// Order flags to set. Accepts any iterable collection of [OrderFlags]
@@ -141,6 +291,12 @@ impl KIAddOrder {
         self.update_input("starttm", timestamp.to_string())
     }
 
+    /// Schedule the order start time for `time`. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn start_at_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("starttm", time.timestamp().to_string())
+    }
+
     /// Order to expire in `secs` seconds
     pub fn expire_in(self, secs: u32) -> Self {
         self.update_input("expiretm", secs.to_string())
@@ -151,18 +307,54 @@ impl KIAddOrder {
         self.update_input("expiretm", timestamp.to_string())
     }
 
+    /// Order to expire at `time`. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn expire_at_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("expiretm", time.timestamp().to_string())
+    }
+
     /// User supplied unsigned 32 bit integer which Kraken will use to demarcate this order for
     /// future reference
     pub fn with_userref(self, userref: u32) -> Self {
         self.update_input("userref", userref.to_string())
     }
 
+    /// Price signal used to trigger stop loss, take profit and trailing stop orders. Defaults to
+    /// the last traded price
+    pub fn with_trigger(self, trigger: OrderTrigger) -> Self {
+        self.update_input("trigger", trigger.to_string())
+    }
+
+    /// Only allow this order to reduce the size of an existing margin position
+    pub fn reduce_only(self, reduce_only: bool) -> Self {
+        self.update_input("reduce_only", reduce_only.to_string())
+    }
+
+    /// Time in force of the order. Defaults to good till cancelled
+    pub fn with_time_in_force(self, timeinforce: TimeInForce) -> Self {
+        self.update_input("timeinforce", timeinforce.to_string())
+    }
+
+    /// Self trade prevention behaviour when this order would match against another order of the
+    /// same user. Defaults to cancelling the newest order
+    pub fn with_self_trade_prevention(self, stptype: SelfTradePrevention) -> Self {
+        self.update_input("stptype", stptype.to_string())
+    }
+
+    /// RFC3339 timestamp (i.e. 2021-04-01T00:18:45Z) after which the matching engine should
+    /// reject the order. Must be between 2 and 60 seconds in the future
+    pub fn with_deadline(self, deadline: String) -> Self {
+        self.update_input("deadline", deadline.replace("+", "%2B"))
+    }
+
     /// Validate inputs on Kraken's servers. Don't submit order
     pub fn validate(self, validate: bool) -> Self {
         self.update_input("validate", validate.to_string())
     }
 
-    /// Closing order to add to the system when this order gets filled
+    /// Closing order to add to the system when this order gets filled. Kraken has no display
+    /// volume for closing orders and rejects iceberg closing orders, which [check()][Self::check]
+    /// reports as [OrderViolation::ClosingOrderType]
     pub fn with_closing_order(self, ordertype: OrderType) -> Self {
         let price1 = ordertype.price1();
         let price2 = ordertype.price2();
@@ -181,10 +373,112 @@ impl KIAddOrder {
         }
     }
 
+    /// Check this order against `info`, the [KOAssetPair] info of its pair, and the current
+    /// system `status` before sending it, so orders Kraken would reject don't count against the
+    /// order rate limit. Fails with a [KError::InvalidOrder] for every [OrderViolation] found.
+    /// Relative and percentage prices are only checked for their precision, and the minimum
+    /// volume isn't checked for volumes in the quote currency or for settle-position orders
+    pub fn check(
+        &self,
+        info: &KOAssetPair,
+        status: &KOSystemStatus,
+    ) -> Result<(), KrakenErrors<KError>> {
+        let param = |key: &str| self.params.get(key).map(String::as_str);
+        let ordertype = param("ordertype").unwrap_or("");
+        let oflags: Vec<&str> =
+            param("oflags").map_or(Vec::new(), |flags| flags.split(',').collect());
+        let mut violations = Vec::new();
+
+        let accepted = match status.status {
+            SystemStatus::Online => true,
+            SystemStatus::PostOnly => {
+                ordertype == "limit" && oflags.contains(&OrderFlags::PostOnly.to_string().as_str())
+            }
+            SystemStatus::LimitOnly => ordertype == "limit" || ordertype == "iceberg",
+            SystemStatus::CancelOnly | SystemStatus::Offline => false,
+        };
+        if !accepted {
+            violations.push(OrderViolation::SystemStatus(status.status));
+        }
+
+        for volume in param("volume").into_iter().chain(param("displayvol")) {
+            if decimal_places(volume) > info.lot_decimals as usize {
+                violations.push(OrderViolation::VolumePrecision {
+                    volume: volume.to_string(),
+                    lot_decimals: info.lot_decimals,
+                });
+            }
+        }
+
+        let quote_volume = oflags.contains(&OrderFlags::QuoteCurrency.to_string().as_str());
+        if let (Some(volume), Some(ordermin), false, false) = (
+            param("volume"),
+            info.ordermin.as_deref(),
+            quote_volume,
+            ordertype == "settle-position",
+        ) {
+            if let (Ok(vol), Ok(min)) = (volume.parse::<f64>(), ordermin.parse::<f64>()) {
+                if vol < min {
+                    violations.push(OrderViolation::BelowMinimum {
+                        volume: volume.to_string(),
+                        ordermin: ordermin.to_string(),
+                    });
+                }
+            }
+        }
+
+        let prices = ["price", "price2", "close%5Bprice%5D", "close%5Bprice2%5D"]
+            .iter()
+            .filter_map(|key| param(key).and_then(decode_price));
+        for price in prices {
+            if decimal_places(&price) > info.pair_decimals as usize {
+                violations.push(OrderViolation::PricePrecision {
+                    price,
+                    pair_decimals: info.pair_decimals,
+                });
+            }
+        }
+
+        if let Some("iceberg") = param("close%5Bordertype%5D") {
+            violations.push(OrderViolation::ClosingOrderType {
+                ordertype: String::from("iceberg"),
+            });
+        }
+
+        if let Some(leverage) = param("leverage").and_then(|lev| lev.split(':').next()) {
+            let available = match param("type") {
+                Some("sell") => &info.leverage_sell,
+                _ => &info.leverage_buy,
+            };
+            match leverage.parse::<u32>() {
+                Ok(leverage) if !available.contains(&leverage) => {
+                    violations.push(OrderViolation::LeverageUnavailable {
+                        leverage,
+                        available: available.clone(),
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(KrakenErrors(
+                violations.into_iter().map(KError::InvalidOrder).collect(),
+            ))
+        }
+    }
+
     fn with_nonce(self) -> Self {
         self.update_input("nonce", KrakenAuth::nonce())
     }
 
+    // Order parameters as built so far. Used to embed this order in an order batch
+    pub(crate) fn params(&self) -> &IndexMap<String, String> {
+        &self.params
+    }
+
     fn format_flag(&mut self, flag: OrderFlags) {
         let listname = String::from("oflags");
         match self.params.get_mut(&listname) {
@@ -194,7 +488,7 @@ impl KIAddOrder {
                     return;
                 }
 
-                *list = format!("{},{}", list, flag.to_string());
+                *list = format!("{},{}", list, flag);
             }
             None => {
                 self.list_mut().insert(listname, flag.to_string());
@@ -202,6 +496,25 @@ impl KIAddOrder {
         }
     }
 }
+
+// Undo the percent encoding of a price and drop its relative prefix. None for percentages, which
+// Kraken resolves to a price itself
+fn decode_price(price: &str) -> Option<String> {
+    let price = crate::api::decode_param(price);
+    if price.ends_with('%') {
+        return None;
+    }
+    Some(price.trim_start_matches(&['+', '-', '#'][..]).to_string())
+}
+
+// Number of significant decimal places of a decimal number
+fn decimal_places(value: &str) -> usize {
+    match value.split_once('.') {
+        Some((_, decimals)) => decimals.trim_end_matches('0').len(),
+        None => 0,
+    }
+}
+
 impl MutateInput for KIAddOrder {
     fn list_mut(&mut self) -> &mut IndexMap<String, String> {
         &mut self.params
@@ -255,3 +568,90 @@ pub struct AddOrderDesc {
     /// Conditional close order description (if order was added successfully)
     pub close: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::KAsset;
+
+    #[test]
+    fn check_against_pair_info() {
+        let info: KOAssetPair = serde_json::from_value(serde_json::json!({
+            "aclass_base": "currency", "aclass_quote": "currency", "altname": "XBTUSD",
+            "base": "XXBT", "fee_volume_currency": "ZUSD", "fees": [[0, 0.26]],
+            "leverage_buy": [2, 3], "leverage_sell": [2], "lot": "unit", "lot_decimals": 8,
+            "lot_multiplier": 1, "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001",
+            "pair_decimals": 1, "quote": "ZUSD", "wsname": "XBT/USD"
+        }))
+        .unwrap();
+        let status = |status: &str| -> KOSystemStatus {
+            serde_json::from_value(serde_json::json!({
+                "status": status, "timestamp": "2021-03-21T14:23:14Z"
+            }))
+            .unwrap()
+        };
+        let pair = KAssetPair(KAsset::XBT, KAsset::USD);
+
+        let order = KIAddOrder::build(
+            pair,
+            TradeType::Buy,
+            OrderType::Limit(String::from("45000.5")),
+            0.5,
+        )
+        .with_order_flags(vec![OrderFlags::PostOnly])
+        .with_leverage(Leverage::Three);
+        assert!(order.check(&info, &status("online")).is_ok());
+        assert!(order.check(&info, &status("post_only")).is_ok());
+
+        // Percentage offsets aren't bound to the decimals of the pair
+        let order = KIAddOrder::build(
+            pair,
+            TradeType::Sell,
+            OrderType::TrailingStop(String::from("+1.25%")),
+            0.5,
+        );
+        assert!(order.check(&info, &status("online")).is_ok());
+
+        let order = KIAddOrder::build(
+            pair,
+            TradeType::Sell,
+            OrderType::StopLossLimit(String::from("#1.25"), String::from("45000")),
+            0.00005,
+        )
+        .with_closing_order(OrderType::Iceberg(
+            String::from("46000"),
+            String::from("0.00001"),
+        ))
+        .with_leverage(Leverage::Three);
+        let errors = order.check(&info, &status("limit_only")).unwrap_err();
+        let violations: Vec<_> = errors
+            .0
+            .into_iter()
+            .map(|error| match error {
+                KError::InvalidOrder(violation) => violation,
+                error => panic!("unexpected error {}", error),
+            })
+            .collect();
+        assert_eq!(
+            violations,
+            vec![
+                OrderViolation::SystemStatus(SystemStatus::LimitOnly),
+                OrderViolation::BelowMinimum {
+                    volume: String::from("0.00005"),
+                    ordermin: String::from("0.0001"),
+                },
+                OrderViolation::PricePrecision {
+                    price: String::from("1.25"),
+                    pair_decimals: 1,
+                },
+                OrderViolation::ClosingOrderType {
+                    ordertype: String::from("iceberg"),
+                },
+                OrderViolation::LeverageUnavailable {
+                    leverage: 3,
+                    available: vec![2],
+                },
+            ]
+        );
+    }
+}
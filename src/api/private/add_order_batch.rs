@@ -0,0 +1,206 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+use crate::error::{self, KError, KrakenErrors};
+// Structs/Enums
+use super::add_order::{AddOrderDesc, KIAddOrder};
+use super::{EndpointInfo, KAssetPair, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Maximum number of orders Kraken accepts in a single batch
+pub const MAX_BATCH_SIZE: usize = 15;
+
+/// Request builder for the Add Order Batch endpoint. The batch is sent as a JSON body
+pub struct KIAddOrderBatch {
+    params: IndexMap<String, String>,
+    count: usize,
+}
+
+impl KIAddOrderBatch {
+    /// Constructor returning a [KrakenInput] builder for the add order batch endpoint.
+    /// Every order in the batch is built with [KIAddOrder] and must be for `pair`. Fails with
+    /// [KError::InvalidBatch] if there are no orders, more than [MAX_BATCH_SIZE] orders, or an
+    /// order for a different asset pair
+    pub fn build<T>(pair: KAssetPair, orders: T) -> Result<Self, KrakenErrors<KError>>
+    where
+        T: IntoIterator<Item = KIAddOrder>,
+    {
+        let batch = KIAddOrderBatch {
+            params: IndexMap::new(),
+            count: 0,
+        }
+        .update_input("pair", pair.to_string());
+
+        let batch = orders
+            .into_iter()
+            .try_fold(batch, |batch, order| batch.with_order(order))?;
+
+        match batch.count {
+            0 => Err(KrakenErrors(vec![KError::InvalidBatch])),
+            _ => Ok(batch),
+        }
+    }
+
+    /// Add another order to the batch. Fails with [KError::InvalidBatch] if the batch is full or
+    /// the order is for a different asset pair than the batch
+    pub fn with_order(mut self, order: KIAddOrder) -> Result<Self, KrakenErrors<KError>> {
+        if self.count >= MAX_BATCH_SIZE || order.params().get("pair") != self.params.get("pair") {
+            return Err(KrakenErrors(vec![KError::InvalidBatch]));
+        }
+
+        // The pair and validate flag are set once for the whole batch. Every other parameter is
+        // nested under the order's index, i.e. close[ordertype] becomes orders[0][close][ordertype],
+        // and sent as the JSON body {"orders": [{"close": {"ordertype": ..}}], ..}
+        let index = self.count;
+        for (key, value) in order.params() {
+            if key == "pair" || key == "validate" || key == "nonce" {
+                continue;
+            }
+            let key = match key.find("%5B") {
+                Some(split) => format!("{}%5D{}", &key[..split], &key[split..]),
+                None => format!("{}%5D", key),
+            };
+            self.params
+                .insert(format!("orders%5B{}%5D%5B{}", index, key), value.clone());
+        }
+        self.count += 1;
+        Ok(self)
+    }
+
+    /// Number of orders in the batch
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Whether the batch holds no orders
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// RFC3339 timestamp (i.e. 2021-04-01T00:18:45Z) after which the matching engine should
+    /// reject the batch. Must be between 2 and 60 seconds in the future
+    pub fn with_deadline(self, deadline: String) -> Self {
+        self.update_input("deadline", deadline.replace("+", "%2B"))
+    }
+
+    /// Validate inputs on Kraken's servers. Don't submit the orders
+    pub fn validate(self, validate: bool) -> Self {
+        self.update_input("validate", validate.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIAddOrderBatch {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIAddOrderBatch {}
+
+impl Input for KIAddOrderBatch {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("AddOrderBatch"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("AddOrderBatch"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Result of a single order in the batch | See [KOAddOrderBatch]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOBatchOrder {
+    /// Order description info (if order was added successfully)
+    pub descr: Option<AddOrderDesc>,
+    /// Transaction ID of the order (if order was added successfully)
+    pub txid: Option<String>,
+    /// Error message if the order could not be added
+    pub error: Option<String>,
+}
+
+impl KOBatchOrder {
+    /// The reason this order could not be added, if it failed
+    pub fn errors(&self) -> Option<KrakenErrors<KError>> {
+        self.error
+            .as_ref()
+            .map(|error| error::generate_errors(vec![error.clone()]))
+    }
+}
+
+/// Response from the Add Order Batch endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOAddOrderBatch {
+    /// Results of each order, in the same order the orders were added to the batch
+    pub orders: Vec<KOBatchOrder>,
+}
+
+impl Output for KOAddOrderBatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::KAsset;
+    use crate::api::{OrderType, TradeType};
+
+    #[test]
+    fn nest_order_params() {
+        let pair = KAssetPair(KAsset::XBT, KAsset::USD);
+        let order = |price: &str| {
+            KIAddOrder::build(
+                pair,
+                TradeType::Buy,
+                OrderType::Limit(price.to_string()),
+                1.0,
+            )
+            .with_closing_order(OrderType::Limit(String::from("40000")))
+            .validate(true)
+        };
+
+        let batch = KIAddOrderBatch::build(pair, vec![order("30000"), order("+5%")])
+            .unwrap()
+            .validate(true);
+        let input = batch.finish();
+        let body: serde_json::Value = serde_json::from_str(&input.json_body().unwrap()).unwrap();
+        assert_eq!(body["pair"], "XBTUSD");
+        assert_eq!(body["validate"], true);
+        assert!(body["nonce"].is_u64());
+        assert_eq!(body["orders"].as_array().unwrap().len(), 2);
+        assert_eq!(body["orders"][0]["price"], "30000");
+        assert_eq!(body["orders"][1]["price"], "+5%");
+        assert_eq!(body["orders"][0]["close"]["ordertype"], "limit");
+        assert!(body["orders"][0].get("validate").is_none());
+
+        let other = KIAddOrder::build(
+            KAssetPair(KAsset::ETH, KAsset::USD),
+            TradeType::Sell,
+            OrderType::Market,
+            1.0,
+        );
+        let batch = KIAddOrderBatch::build(pair, vec![order("30000")]).unwrap();
+        assert!(batch.with_order(other).is_err());
+        assert!(KIAddOrderBatch::build(pair, (0..16).map(|_| order("30000"))).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+use crate::error::{KError, KrakenErrors};
+// Structs/Enums
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Maximum number of orders Kraken accepts in a single cancel batch
+pub const MAX_CANCEL_BATCH_SIZE: usize = 50;
+
+/// Request builder for the Cancel Order Batch endpoint. The batch is sent as a JSON body
+pub struct KICancelOrderBatch {
+    params: IndexMap<String, String>,
+    txids: usize,
+    userrefs: usize,
+}
+
+impl KICancelOrderBatch {
+    /// Constructor returning a [KrakenInput] builder for the cancel order batch endpoint.
+    /// txids are the transaction IDs of the orders to cancel. Fails with [KError::InvalidBatch]
+    /// if there are no orders or more than [MAX_CANCEL_BATCH_SIZE] orders
+    pub fn build<T>(txids: T) -> Result<Self, KrakenErrors<KError>>
+    where
+        T: IntoIterator<Item = String>,
+    {
+        let batch = KICancelOrderBatch {
+            params: IndexMap::new(),
+            txids: 0,
+            userrefs: 0,
+        };
+
+        let batch = txids
+            .into_iter()
+            .try_fold(batch, |batch, txid| batch.with_txid(txid))?;
+
+        match batch.count() {
+            0 => Err(KrakenErrors(vec![KError::InvalidBatch])),
+            _ => Ok(batch),
+        }
+    }
+
+    /// Add the transaction ID of another order to cancel. Fails with [KError::InvalidBatch] if
+    /// the batch is full
+    pub fn with_txid(self, txid: String) -> Result<Self, KrakenErrors<KError>> {
+        if self.count() >= MAX_CANCEL_BATCH_SIZE {
+            return Err(KrakenErrors(vec![KError::InvalidBatch]));
+        }
+        let key = format!("orders%5B{}%5D", self.txids);
+        let mut batch = self.update_input(&key, txid);
+        batch.txids += 1;
+        Ok(batch)
+    }
+
+    /// Add the user reference ID of orders to cancel. Fails with [KError::InvalidBatch] if the
+    /// batch is full
+    pub fn with_userref(self, userref: u32) -> Result<Self, KrakenErrors<KError>> {
+        if self.count() >= MAX_CANCEL_BATCH_SIZE {
+            return Err(KrakenErrors(vec![KError::InvalidBatch]));
+        }
+        // Kept apart from the txids so the userref is sent as a number
+        let key = format!("userrefs%5B{}%5D", self.userrefs);
+        let mut batch = self.update_input(&key, userref.to_string());
+        batch.userrefs += 1;
+        Ok(batch)
+    }
+
+    fn count(&self) -> usize {
+        self.txids + self.userrefs
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KICancelOrderBatch {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KICancelOrderBatch {}
+
+impl Input for KICancelOrderBatch {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("CancelOrderBatch"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("CancelOrderBatch"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Cancel Order Batch endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOCancelOrderBatch {
+    /// Number of orders canceled
+    pub count: u32,
+}
+
+impl Output for KOCancelOrderBatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_txids() {
+        let input = KICancelOrderBatch::build(vec![String::from("OQCLML-BW3P3-BUCMWZ")])
+            .unwrap()
+            .with_userref(42)
+            .unwrap()
+            .finish();
+        let body = input.json_body().unwrap();
+        assert!(body.ends_with(r#","orders":["OQCLML-BW3P3-BUCMWZ",42]}"#));
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let nonce = body["nonce"].clone();
+        assert!(nonce.is_u64());
+        assert_eq!(
+            body,
+            serde_json::json!({ "orders": ["OQCLML-BW3P3-BUCMWZ", 42], "nonce": nonce })
+        );
+        assert!(KICancelOrderBatch::build(Vec::new()).is_err());
+    }
+}
@@ -46,11 +46,23 @@ impl KIClosedOrders {
         self.update_input("start", timestamp.to_string())
     }
 
+    /// Starting time to filter output by. Exclusive. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn starting_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("start", time.timestamp().to_string())
+    }
+
     /// Ending Unix timestamp to filter output by. Inclusive
     pub fn ending_timestamp(self, timestamp: u64) -> Self {
         self.update_input("end", timestamp.to_string())
     }
 
+    /// Ending time to filter output by. Inclusive. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn ending_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("end", time.timestamp().to_string())
+    }
+
     /// Starting transaction ID to filter output by. Exclusive
     pub fn starting_txid(self, txid: String) -> Self {
         self.update_input("start", txid)
@@ -0,0 +1,83 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Create Subaccount endpoint
+pub struct KICreateSubaccount {
+    params: IndexMap<String, String>,
+}
+
+impl KICreateSubaccount {
+    /// Constructor returning a [KrakenInput] builder for the create subaccount endpoint.
+    /// Must be sent with the credentials of the master account
+    pub fn build(username: String, email: String) -> KICreateSubaccount {
+        let subaccount = KICreateSubaccount {
+            params: IndexMap::new(),
+        };
+        subaccount.with_username(username).with_email(email)
+    }
+
+    /// Update the username of the subaccount
+    pub fn with_username(self, username: String) -> Self {
+        self.update_input("username", username)
+    }
+
+    /// Update the email address of the subaccount
+    pub fn with_email(self, email: String) -> Self {
+        self.update_input("email", email.replace("+", "%2B").replace("@", "%40"))
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KICreateSubaccount {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KICreateSubaccount {}
+
+impl Input for KICreateSubaccount {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("CreateSubaccount"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("CreateSubaccount"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Create Subaccount endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOCreateSubaccount {
+    /// Whether the subaccount was created
+    pub success: bool,
+}
+
+impl Output for KOCreateSubaccount {}
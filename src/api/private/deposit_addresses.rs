@@ -0,0 +1,124 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Deposit Addresses endpoint
+pub struct KIDepositAddresses {
+    params: IndexMap<String, String>,
+}
+
+impl KIDepositAddresses {
+    /// Constructor returning a [KrakenInput] builder for the get deposit addresses endpoint.
+    /// asset is the asset being deposited and method is the name of the deposit method as
+    /// returned by the [Get Deposit Methods][super::deposit_methods::KIDepositMethods] endpoint
+    pub fn build(asset: KAsset, method: String) -> KIDepositAddresses {
+        let addresses = KIDepositAddresses {
+            params: IndexMap::new(),
+        };
+        addresses.with_asset(asset).with_method(method)
+    }
+
+    /// Update the asset being deposited
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the name of the deposit method
+    pub fn with_method(self, method: String) -> Self {
+        self.update_input("method", method)
+    }
+
+    /// Whether or not to generate a new address
+    pub fn generate_new(self, new: bool) -> Self {
+        if new {
+            self.update_input("new", String::from("true"))
+        } else {
+            self.update_input("new", String::from(""))
+        }
+    }
+
+    /// Amount you wish to deposit (only required for method=Bitcoin Lightning)
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIDepositAddresses {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIDepositAddresses {}
+
+impl Input for KIDepositAddresses {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("DepositAddresses"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("DepositAddresses"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Deposit address info | See [KODepositAddresses]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KODepositAddress {
+    /// Deposit address
+    pub address: String,
+    /// Expiration time in unix timestamp, or 0 if not expiring
+    #[serde(deserialize_with = "super::deserialize_unix_time")]
+    pub expiretm: u64,
+    /// Whether or not address has ever been used
+    pub new: Option<bool>,
+    /// Tag required by some assets (i.e. XRP destination tag)
+    pub tag: Option<String>,
+    /// Memo required by some assets (i.e. XLM memo)
+    pub memo: Option<String>,
+}
+
+impl KODepositAddress {
+    /// Expiration time of the address in unix timestamp if the address expires
+    pub fn expires(&self) -> Option<u64> {
+        match self.expiretm {
+            0 => None,
+            time => Some(time),
+        }
+    }
+}
+
+/// Response from the Get Deposit Addresses endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KODepositAddresses {
+    /// List of deposit addresses for the requested asset and method
+    pub addresses: Vec<KODepositAddress>,
+}
+
+impl Output for KODepositAddresses {}
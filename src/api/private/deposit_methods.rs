@@ -0,0 +1,99 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Deposit Methods endpoint
+pub struct KIDepositMethods {
+    params: IndexMap<String, String>,
+}
+
+impl KIDepositMethods {
+    /// Constructor returning a [KrakenInput] builder for the get deposit methods endpoint.
+    /// asset is the asset being deposited
+    pub fn build(asset: KAsset) -> KIDepositMethods {
+        let methods = KIDepositMethods {
+            params: IndexMap::new(),
+        };
+        methods.with_asset(asset)
+    }
+
+    /// Update the asset being deposited. Useful for templating or iterating over a list of assets
+    /// without allocation
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIDepositMethods {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIDepositMethods {}
+
+impl Input for KIDepositMethods {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("DepositMethods"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("DepositMethods"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Deposit method info | See [KODepositMethods]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KODepositMethod {
+    /// Name of deposit method
+    pub method: String,
+    /// Maximum net amount that can be deposited right now (None if there is no limit)
+    #[serde(deserialize_with = "super::deserialize_funding_limit")]
+    pub limit: Option<String>,
+    /// Amount of fees that will be paid
+    pub fee: Option<String>,
+    /// Whether or not method has an address setup fee
+    #[serde(rename = "address-setup-fee")]
+    pub address_setup_fee: Option<String>,
+    /// Whether new addresses can be generated for this method
+    #[serde(rename = "gen-address")]
+    pub gen_address: Option<bool>,
+    /// Minimum net amount that can be deposited
+    pub minimum: Option<String>,
+}
+
+/// Response from the Get Deposit Methods endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KODepositMethods {
+    /// List of deposit methods available for the requested asset
+    pub methods: Vec<KODepositMethod>,
+}
+
+impl Output for KODepositMethods {}
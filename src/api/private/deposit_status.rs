@@ -0,0 +1,111 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KOFundingStatus, KOFundingStatusProp, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Status of Recent Deposits endpoint
+pub struct KIDepositStatus {
+    params: IndexMap<String, String>,
+}
+
+impl KIDepositStatus {
+    /// Constructor returning a [KrakenInput] builder for the get status of recent deposits
+    /// endpoint. asset is the asset being deposited
+    pub fn build(asset: KAsset) -> KIDepositStatus {
+        let status = KIDepositStatus {
+            params: IndexMap::new(),
+        };
+        status.with_asset(asset)
+    }
+
+    /// Update the asset being deposited
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Filter for a specific name of deposit method
+    pub fn with_method(self, method: String) -> Self {
+        self.update_input("method", method)
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIDepositStatus {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIDepositStatus {}
+
+impl Input for KIDepositStatus {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("DepositStatus"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("DepositStatus"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Deposit status info | See [KODepositStatus]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KODepositInfo {
+    /// Name of deposit method
+    pub method: String,
+    /// Asset class
+    pub aclass: String,
+    /// Asset
+    pub asset: String,
+    /// Reference ID
+    pub refid: String,
+    /// Method transaction ID
+    pub txid: Option<String>,
+    /// Method transaction information
+    pub info: String,
+    /// Amount deposited
+    pub amount: String,
+    /// Fees paid
+    pub fee: Option<String>,
+    /// Unix timestamp when request was made
+    pub time: u64,
+    /// Status of deposit
+    pub status: KOFundingStatus,
+    /// Additional status properties (if available)
+    #[serde(rename = "status-prop")]
+    pub status_prop: Option<KOFundingStatusProp>,
+}
+
+/// Response from the Get Status of Recent Deposits endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KODepositStatus {
+    /// List of recent deposits for the requested asset
+    pub deposits: Vec<KODepositInfo>,
+}
+
+impl Output for KODepositStatus {}
@@ -0,0 +1,197 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::api::encode_price;
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAssetPair, KrakenInput, MethodType, OrderFlags};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Edit Order endpoint
+pub struct KIEditOrder {
+    params: IndexMap<String, String>,
+}
+
+impl KIEditOrder {
+    /// Constructor returning a [KrakenInput] builder for the edit order endpoint.
+    /// txid is the transaction ID of the open order to edit. Only the parameters set on the
+    /// builder are changed, everything else is carried over from the original order
+    pub fn build(pair: KAssetPair, txid: String) -> KIEditOrder {
+        let editorder = KIEditOrder {
+            params: IndexMap::new(),
+        };
+        editorder.with_pair(pair).with_txid(txid)
+    }
+
+    /// Constructor returning a [KrakenInput] builder for the edit order endpoint, selecting the
+    /// open order to edit by the userref it was placed with
+    pub fn build_with_userref(pair: KAssetPair, userref: u32) -> KIEditOrder {
+        let editorder = KIEditOrder {
+            params: IndexMap::new(),
+        };
+        editorder.with_pair(pair).with_userref(userref)
+    }
+
+    /// Update the asset pair of the order to edit. Useful for templating
+    pub fn with_pair(self, pair: KAssetPair) -> Self {
+        self.update_input("pair", pair.to_string())
+    }
+
+    /// Update the transaction ID of the order to edit
+    pub fn with_txid(self, txid: String) -> Self {
+        self.update_input("txid", txid)
+    }
+
+    /// Select the order to edit by its user reference ID instead of its transaction ID
+    pub fn with_userref(self, userref: u32) -> Self {
+        self.update_input("txid", userref.to_string())
+    }
+
+    /// New user reference ID for the edited order
+    pub fn with_new_userref(self, userref: u32) -> Self {
+        self.update_input("userref", userref.to_string())
+    }
+
+    /// New order volume in lots
+    pub fn with_volume(self, volume: f64) -> Self {
+        self.update_input("volume", volume.to_string())
+    }
+
+    /// New primary price (limit price, stop loss trigger price etc.). Accepts the same relative
+    /// price prefixes as [OrderType][super::OrderType]
+    pub fn with_price(self, price: String) -> Self {
+        self.update_input("price", encode_price(&price))
+    }
+
+    /// New secondary price (triggered limit price for stop loss limit and take profit limit
+    /// orders). Accepts the same relative price prefixes as [OrderType][super::OrderType]
+    pub fn with_price2(self, price: String) -> Self {
+        self.update_input("price2", encode_price(&price))
+    }
+
+    /// Order flags to set on the edited order. Accepts any iterable collection of [OrderFlags]
+    pub fn with_order_flags<T>(mut self, flags: T) -> Self
+    where
+        T: IntoIterator<Item = OrderFlags>,
+    {
+        flags.into_iter().for_each(|flag| self.format_flag(flag));
+        self
+    }
+
+    /// RFC3339 timestamp (i.e. 2021-04-01T00:18:45Z) after which the matching engine should
+    /// reject the edit request. Must be between 2 and 60 seconds in the future
+    pub fn with_deadline(self, deadline: String) -> Self {
+        self.update_input("deadline", deadline.replace("+", "%2B"))
+    }
+
+    /// Respond as soon as the original order is cancelled, with the replacement order still
+    /// pending, instead of once the order is completely replaced. Doesn't change whether the
+    /// original order is cancelled
+    pub fn cancel_response(self, cancel: bool) -> Self {
+        self.update_input("cancel_response", cancel.to_string())
+    }
+
+    /// Validate inputs on Kraken's servers. Don't submit the edit
+    pub fn validate(self, validate: bool) -> Self {
+        self.update_input("validate", validate.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+
+    fn format_flag(&mut self, flag: OrderFlags) {
+        let listname = String::from("oflags");
+        match self.params.get_mut(&listname) {
+            Some(list) => {
+                // Silently disallow adding the same input to the list multiple times
+                if list.contains(&flag.to_string()) {
+                    return;
+                }
+
+                *list = format!("{},{}", list, flag);
+            }
+            None => {
+                self.list_mut().insert(listname, flag.to_string());
+            }
+        }
+    }
+}
+
+impl MutateInput for KIEditOrder {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIEditOrder {}
+
+impl Input for KIEditOrder {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("EditOrder"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("EditOrder"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Status of an order edit | See [KOEditOrder]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KOEditOrderStatus {
+    /// Order was successfully edited
+    Ok,
+    /// Order edit failed. See the error message for details
+    Err,
+}
+
+/// Textual description of the edited order | See [KOEditOrder]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct EditOrderDesc {
+    /// Order description
+    pub order: String,
+}
+
+/// Response from the Edit Order endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOEditOrder {
+    /// Order description info
+    pub descr: Option<EditOrderDesc>,
+    /// Transaction ID of the new order (if the order was edited successfully)
+    pub txid: Option<String>,
+    /// Transaction ID of the original order
+    pub originaltxid: Option<String>,
+    /// Updated volume
+    pub volume: Option<String>,
+    /// Updated price
+    pub price: Option<String>,
+    /// Updated price2
+    pub price2: Option<String>,
+    /// Number of orders cancelled (either 0 or 1)
+    pub orders_cancelled: Option<u32>,
+    /// Status of the edit
+    pub status: KOEditOrderStatus,
+    /// Error message if the edit was unsuccessful
+    pub error_message: Option<String>,
+}
+
+impl Output for KOEditOrder {}
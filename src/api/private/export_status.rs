@@ -0,0 +1,125 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::add_export::ExportReport;
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Export Report Status endpoint
+pub struct KIExportStatus {
+    params: IndexMap<String, String>,
+}
+
+impl KIExportStatus {
+    /// Constructor returning a [KrakenInput] builder for the get export report status endpoint.
+    /// Lists the status of every report of the given type
+    pub fn build(report: ExportReport) -> KIExportStatus {
+        let status = KIExportStatus {
+            params: IndexMap::new(),
+        };
+        status.with_report(report)
+    }
+
+    /// Update the type of report to query
+    pub fn with_report(self, report: ExportReport) -> Self {
+        self.update_input("report", report.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIExportStatus {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIExportStatus {}
+
+impl Input for KIExportStatus {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("ExportStatus"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("ExportStatus"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Processing status of a data export report | See [KOExportInfo]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub enum KOExportState {
+    /// Report is waiting to be processed
+    Queued,
+    /// Report is being generated
+    Processing,
+    /// Report is ready to be retrieved
+    Processed,
+}
+
+/// Data export report info | See [KOExportStatus]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOExportInfo {
+    /// Report ID
+    pub id: String,
+    /// Report description
+    pub descr: String,
+    /// File format of the report
+    pub format: String,
+    /// Type of report
+    pub report: String,
+    pub subtype: Option<String>,
+    /// Status of the report
+    pub status: KOExportState,
+    pub flags: Option<String>,
+    /// Fields included in the report
+    pub fields: String,
+    /// Unix timestamp of report request
+    pub createdtm: String,
+    /// Unix timestamp of report expiration
+    pub expiretm: String,
+    /// Unix timestamp of report processing start time
+    pub starttm: String,
+    /// Unix timestamp of report processing completion time
+    pub completedtm: String,
+    /// Unix timestamp of the report data start time
+    pub datastarttm: String,
+    /// Unix timestamp of the report data end time
+    pub dataendtm: String,
+    /// Asset class
+    pub aclass: Option<String>,
+    /// Asset
+    pub asset: Option<String>,
+}
+
+/// Response from the Get Export Report Status endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOExportStatus {
+    /// List of reports of the requested type
+    pub reports: Vec<KOExportInfo>,
+}
+
+impl Output for KOExportStatus {}
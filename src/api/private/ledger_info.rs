@@ -1,16 +1,19 @@
 use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::auth::KrakenAuth;
 // Structs/Enums
 use super::{EndpointInfo, KAsset, KrakenInput, LedgerType, MethodType};
 
 // Traits
-use super::{Input, InputList, InputListItem, IntoInputList, MutateInput, UpdateInput};
+use super::{Input, InputList, InputListItem, IntoInputList, MutateInput, Output, UpdateInput};
 
 pub use super::KOLedgerInfo;
 pub use super::KOLedgers;
 
-/// Request builder for the Get Ledgers Info endpoint
+/// Request builder for the Get Ledgers Info endpoint. Deserialize the response as
+/// [KOLedgerHistory]. Kraken nests the entries under `ledger`, so [KOLedgers] doesn't match it
 pub struct KILedgerInfo {
     params: IndexMap<String, String>,
 }
@@ -53,11 +56,23 @@ impl KILedgerInfo {
         self.update_input("start", timestamp.to_string())
     }
 
+    /// Starting time to filter output by. Exclusive. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn starting_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("start", time.timestamp().to_string())
+    }
+
     /// Ending Unix timestamp to filter output by. Inclusive
     pub fn ending_timestamp(self, timestamp: u64) -> Self {
         self.update_input("end", timestamp.to_string())
     }
 
+    /// Ending time to filter output by. Inclusive. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn ending_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("end", time.timestamp().to_string())
+    }
+
     /// Starting ledger ID to filter output by. Exclusive
     pub fn starting_legid(self, legid: String) -> Self {
         self.update_input("start", legid)
@@ -123,3 +138,14 @@ impl InputListItem for KILedgerInfo {
 }
 
 impl InputList for KILedgerInfo {}
+
+/// Response from the Get Ledgers Info endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOLedgerHistory {
+    /// Map with the ledger ID as the key and the ledger info as the value
+    pub ledger: HashMap<String, KOLedgerInfo>,
+    /// Total number of ledger entries matching the request
+    pub count: u32,
+}
+
+impl Output for KOLedgerHistory {}
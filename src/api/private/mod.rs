@@ -8,14 +8,16 @@
 //! [KrakenClient][super::super::client::KrakenClient] otherwise requests sent to
 //! private endpoints will panic before being sent to Kraken
 
+use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Structs/Enums
 use super::asset::{KAsset, KAssetPair};
 use super::{
-    EndpointInfo, KrakenInput, LedgerType, MethodType, OrderCloseTime, OrderFlags, OrderType,
-    TradeHistoryType, TradeType,
+    EndpointInfo, KDecimal, KTimestamp, KrakenInput, LedgerType, MethodType, OrderCloseTime,
+    OrderFlags, OrderTrigger, OrderType, SelfTradePrevention, TimeInForce, TradeHistoryType,
+    TradeType,
 };
 
 // Traits
@@ -57,15 +59,84 @@ pub mod trade_volume;
 /// Add standard order endpoint
 pub mod add_order;
 
+/// Add order batch endpoint
+pub mod add_order_batch;
+
+/// Edit open order endpoint
+pub mod edit_order;
+
 /// Cancel open order endpoint
 pub mod cancel_order;
 
+/// Cancel order batch endpoint
+pub mod cancel_order_batch;
+
 /// Cancel all orders endpoint
 pub mod cancel_all_orders;
 
 /// Cancel all orders after ... endpoint
 pub mod cancel_on_timeout;
 
+/// Get websockets token endpoint
+pub mod websockets_token;
+
+/// Get deposit methods endpoint
+pub mod deposit_methods;
+
+/// Get deposit addresses endpoint
+pub mod deposit_addresses;
+
+/// Get status of recent deposits endpoint
+pub mod deposit_status;
+
+/// Get withdrawal information endpoint
+pub mod withdraw_info;
+
+/// Withdraw funds endpoint
+pub mod withdraw;
+
+/// Get status of recent withdrawals endpoint
+pub mod withdraw_status;
+
+/// Request withdrawal cancelation endpoint
+pub mod withdraw_cancel;
+
+/// Request export report endpoint
+pub mod add_export;
+
+/// Get export report status endpoint
+pub mod export_status;
+
+/// Retrieve data export endpoint
+pub mod retrieve_export;
+
+/// Delete export report endpoint
+pub mod remove_export;
+
+/// Stake asset endpoint
+pub mod stake;
+
+/// Unstake asset endpoint
+pub mod unstake;
+
+/// List of stakeable assets endpoint
+pub mod staking_assets;
+
+/// Get pending staking transactions endpoint
+pub mod staking_pending;
+
+/// List of staking transactions endpoint
+pub mod staking_transactions;
+
+/// Request wallet transfer endpoint
+pub mod wallet_transfer;
+
+/// Create subaccount endpoint
+pub mod create_subaccount;
+
+/// Account transfer endpoint
+pub mod account_transfer;
+
 /// Order description data | See [KOOrderInfo]
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOOrderDescription {
@@ -110,27 +181,32 @@ pub struct KOOrderInfo {
     /// status of order:
     pub status: KOOrderStatus,
     /// unix timestamp of when order was placed
-    pub opentm: f64,
-    /// unix timestamp of order start time (or 0 if not set)
-    pub starttm: f64,
-    /// unix timestamp of order end time (or 0 if not set)
-    pub expiretm: f64,
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub opentm: KTimestamp,
+    /// unix timestamp of order start time, or 0 (the Unix epoch with the `timestamps` feature) if
+    /// not set
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub starttm: KTimestamp,
+    /// unix timestamp of order end time, or 0 (the Unix epoch with the `timestamps` feature) if
+    /// not set
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub expiretm: KTimestamp,
     /// order description info
     pub descr: KOOrderDescription,
     /// volume of order (base currency unless viqc set in oflags)
-    pub vol: String,
+    pub vol: KDecimal,
     /// volume executed (base currency unless viqc set in oflags)
-    pub vol_exec: String,
+    pub vol_exec: KDecimal,
     /// total cost (quote currency unless unless viqc set in oflags)
-    pub cost: String,
+    pub cost: KDecimal,
     /// total fee (quote currency)
-    pub fee: String,
+    pub fee: KDecimal,
     /// average price (quote currency unless viqc set in oflags)
-    pub price: String,
+    pub price: KDecimal,
     /// stop price (quote currency, for trailing stops)
-    pub stopprice: Option<String>,
+    pub stopprice: Option<KDecimal>,
     /// triggered limit price (quote currency, when limit based order type triggered)
-    pub limitprice: Option<String>,
+    pub limitprice: Option<KDecimal>,
     /// comma delimited list of miscellaneous info:
     /// + stopped = triggered by stop price
     /// + touched = triggered by touch price
@@ -147,7 +223,8 @@ pub struct KOOrderInfo {
     pub trades: Option<Vec<String>>,
     /// unix timestamp of when order was closed. Field only present when calling ClosedOrders
     /// endpoint
-    pub closetm: Option<f64>,
+    #[serde(default, deserialize_with = "crate::api::deserialize_opt_timestamp")]
+    pub closetm: Option<KTimestamp>,
     /// additional info on status (if any). Field only present when calling ClosedOrders
     pub reason: Option<String>,
 }
@@ -159,22 +236,23 @@ pub struct KOTradeData {
     /// Order responsible for execution of trade
     pub ordertxid: String,
     pub pair: String,
-    pub time: f64,
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub time: KTimestamp,
     #[serde(rename = "type")]
     pub tradetype: String,
     pub ordertype: String,
-    pub price: String,
-    pub cost: String,
-    pub fee: String,
-    pub vol: String,
-    pub margin: Option<String>,
+    pub price: KDecimal,
+    pub cost: KDecimal,
+    pub fee: KDecimal,
+    pub vol: KDecimal,
+    pub margin: Option<KDecimal>,
     pub misc: String,
     pub posstatus: Option<String>,
-    pub cprice: Option<String>,
-    pub cfee: Option<String>,
-    pub cvol: Option<String>,
-    pub cmargin: Option<String>,
-    pub net: Option<String>,
+    pub cprice: Option<KDecimal>,
+    pub cfee: Option<KDecimal>,
+    pub cvol: Option<KDecimal>,
+    pub cmargin: Option<KDecimal>,
+    pub net: Option<KDecimal>,
     pub trades: Option<String>,
 }
 
@@ -183,14 +261,15 @@ pub struct KOTradeData {
 pub struct KOLedgerInfo {
     /// Order responsible for execution of trade
     pub refid: String,
-    pub time: f64,
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub time: KTimestamp,
     #[serde(rename = "type")]
     pub ledgertype: String,
     pub aclass: String,
     pub asset: String,
-    pub amount: String,
-    pub fee: String,
-    pub balance: Option<String>,
+    pub amount: KDecimal,
+    pub fee: KDecimal,
+    pub balance: Option<KDecimal>,
 }
 
 /// Response from the Get Ledgers Info or Query Ledgers endpoints | See
@@ -204,3 +283,113 @@ pub struct KOLedgers {
 }
 
 impl Output for KOLedgers {}
+
+/// Status of a deposit or withdrawal | See
+/// [KODepositInfo][deposit_status::KODepositInfo] -
+/// [KOWithdrawalInfo][withdraw_status::KOWithdrawalInfo]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub enum KOFundingStatus {
+    /// Funding request received, not yet processed
+    Initial,
+    /// Funding request is being processed
+    Pending,
+    /// Funding has been sent or received but is not yet final
+    Settled,
+    /// Funding completed successfully
+    Success,
+    /// Funding request failed
+    Failure,
+}
+
+/// Additional status properties of a deposit or withdrawal | See
+/// [KODepositInfo][deposit_status::KODepositInfo] -
+/// [KOWithdrawalInfo][withdraw_status::KOWithdrawalInfo]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum KOFundingStatusProp {
+    /// A return transaction initiated by Kraken
+    Return,
+    /// Funding is on hold pending review
+    Onhold,
+    /// Cancelation requested
+    CancelPending,
+    /// Canceled
+    Canceled,
+    /// Cancelation requested but was denied
+    CancelDenied,
+}
+
+/// Type of staking transaction | See [KOStakingTransaction]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KOStakingType {
+    /// Funds being staked
+    Bonding,
+    /// Staking reward paid out
+    Reward,
+    /// Funds being unstaked
+    Unbonding,
+}
+
+/// Staking transaction info | See
+/// [KOStakingPending][staking_pending::KOStakingPending] -
+/// [KOStakingTransactions][staking_transactions::KOStakingTransactions]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStakingTransaction {
+    /// Staking method
+    pub method: String,
+    /// Asset class
+    pub aclass: String,
+    /// Asset
+    pub asset: String,
+    /// Reference ID of the transaction
+    pub refid: String,
+    /// Amount of the transaction
+    pub amount: String,
+    /// Fees paid
+    pub fee: String,
+    /// Unix timestamp when the transaction was initiated
+    pub time: u64,
+    /// Status of the transaction
+    pub status: KOFundingStatus,
+    /// Type of transaction
+    #[serde(rename = "type")]
+    pub stakingtype: KOStakingType,
+    /// Unix timestamp from the start of bond period (applicable only to bonding transactions)
+    pub bond_start: Option<u64>,
+    /// Unix timestamp of the end of bond period (applicable only to bonding transactions)
+    pub bond_end: Option<u64>,
+}
+
+// Funding limits are sent as `false` when there is no limit and as the limit amount otherwise
+pub(crate) fn deserialize_funding_limit<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Bool(_) => Ok(None),
+        serde_json::Value::String(amount) => Ok(Some(amount)),
+        other => Err(serde::de::Error::custom(format!(
+            "invalid funding limit: {}",
+            other
+        ))),
+    }
+}
+
+// Some funding timestamps are sent as strings, others as numbers
+pub(crate) fn deserialize_unix_time<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Time {
+        Number(u64),
+        Text(String),
+    }
+
+    match Time::deserialize(deserializer)? {
+        Time::Number(time) => Ok(time),
+        Time::Text(time) => time.parse().map_err(serde::de::Error::custom),
+    }
+}
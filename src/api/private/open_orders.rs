@@ -85,7 +85,38 @@ impl UpdateInput for KIOpenOrders {}
 /// Response from the Get Open Orders endpoint
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOOpenOrders {
+    /// Map with the transaction ID as the key and order info as the value. Kraken sends these
+    /// under `open`
+    #[serde(rename = "open")]
     pub orders: HashMap<String, KOOrderInfo>,
 }
 
 impl Output for KOOpenOrders {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_open_orders() {
+        let open: KOOpenOrders = serde_json::from_value(serde_json::json!({
+            "open": { "OQCLML-BW3P3-BUCMWZ": {
+                "refid": null, "userref": 0, "status": "open", "opentm": 1616665496.7808,
+                "starttm": 0, "expiretm": 0, "vol": "1.25000000", "vol_exec": "0.37500000",
+                "cost": "11253.7", "fee": "0.00000", "price": "30010.0", "misc": "",
+                "oflags": "fciq",
+                "descr": {
+                    "pair": "XBTUSD", "type": "buy", "ordertype": "limit", "price": "30010.0",
+                    "price2": "0", "leverage": "none",
+                    "order": "buy 1.25000000 XBTUSD @ limit 30010.0", "close": ""
+                }
+            }}
+        }))
+        .unwrap();
+        let order = &open.orders["OQCLML-BW3P3-BUCMWZ"];
+        assert_eq!(order.descr.pair, "XBTUSD");
+
+        let json = serde_json::to_value(&open).unwrap();
+        assert!(json["open"]["OQCLML-BW3P3-BUCMWZ"].is_object());
+    }
+}
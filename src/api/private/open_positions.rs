@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use crate::auth::KrakenAuth;
 // Structs/Enums
-use super::{EndpointInfo, KrakenInput, MethodType};
+use super::{EndpointInfo, KTimestamp, KrakenInput, MethodType};
 
 // Traits
 use super::{Input, InputList, InputListItem, IntoInputList, MutateInput, Output, UpdateInput};
@@ -119,7 +119,8 @@ pub struct KOPositionInfo {
     /// Order responsible for execution of trade
     pub ordertxid: String,
     pub pair: String,
-    pub time: f64,
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub time: KTimestamp,
     #[serde(rename = "type")]
     pub tradetype: String,
     pub ordertype: String,
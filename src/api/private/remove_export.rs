@@ -0,0 +1,103 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// How a data export report is removed
+pub enum ExportRemoval {
+    /// Cancel a queued or processing report
+    Cancel,
+    /// Delete a processed report
+    Delete,
+}
+
+impl Display for ExportRemoval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportRemoval::Cancel => write!(f, "cancel"),
+            ExportRemoval::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+/// Request builder for the Delete Export Report endpoint
+pub struct KIRemoveExport {
+    params: IndexMap<String, String>,
+}
+
+impl KIRemoveExport {
+    /// Constructor returning a [KrakenInput] builder for the delete export report endpoint.
+    /// id is the report ID returned by the [Request Export Report][super::add_export::KIAddExport]
+    /// endpoint
+    pub fn build(id: String, removal: ExportRemoval) -> KIRemoveExport {
+        let remove = KIRemoveExport {
+            params: IndexMap::new(),
+        };
+        remove.with_id(id).with_removal(removal)
+    }
+
+    /// Update the ID of the report to remove
+    pub fn with_id(self, id: String) -> Self {
+        self.update_input("id", id)
+    }
+
+    /// Update whether the report is cancelled or deleted
+    pub fn with_removal(self, removal: ExportRemoval) -> Self {
+        self.update_input("type", removal.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIRemoveExport {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIRemoveExport {}
+
+impl Input for KIRemoveExport {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("RemoveExport"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("RemoveExport"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Delete Export Report endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KORemoveExport {
+    /// Whether deletion was successful
+    pub delete: Option<bool>,
+    /// Whether cancellation was successful
+    pub cancel: Option<bool>,
+}
+
+impl Output for KORemoveExport {}
@@ -0,0 +1,307 @@
+use indexmap::map::IndexMap;
+use serde::Deserialize;
+use std::io::{Cursor, Read};
+
+use crate::auth::KrakenAuth;
+use crate::error::{KError, KrakenErrors};
+// Structs/Enums
+use super::{EndpointInfo, KDecimal, KOLedgerInfo, KOTradeData, KrakenInput, MethodType};
+use crate::api::{timestamp_from_secs, KTimestamp, KrakenResult};
+
+// Traits
+use super::{Input, MutateInput, UpdateInput};
+use crate::api::BinaryOutput;
+
+/// Request builder for the Retrieve Data Export endpoint
+///
+/// # Note
+/// The report is returned as a zip archive rather than JSON so the request must be sent with
+/// [request_binary][crate::client::KrakenClient::request_binary] instead of
+/// [request][crate::client::KrakenClient::request]
+pub struct KIRetrieveExport {
+    params: IndexMap<String, String>,
+}
+
+impl KIRetrieveExport {
+    /// Constructor returning a [KrakenInput] builder for the retrieve data export endpoint.
+    /// id is the report ID returned by the [Request Export Report][super::add_export::KIAddExport]
+    /// endpoint
+    pub fn build(id: String) -> KIRetrieveExport {
+        let retrieve = KIRetrieveExport {
+            params: IndexMap::new(),
+        };
+        retrieve.with_id(id)
+    }
+
+    /// Update the ID of the report to retrieve
+    pub fn with_id(self, id: String) -> Self {
+        self.update_input("id", id)
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIRetrieveExport {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIRetrieveExport {}
+
+impl Input for KIRetrieveExport {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("RetrieveExport"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("RetrieveExport"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Retrieve Data Export endpoint. Holds the zip archive containing the report
+#[derive(Debug)]
+pub struct KORetrieveExport {
+    /// Raw bytes of the zip archive
+    pub data: Vec<u8>,
+}
+
+impl BinaryOutput for KORetrieveExport {
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        KORetrieveExport { data: bytes }
+    }
+}
+
+impl KORetrieveExport {
+    /// Extract the report from the zip archive. Returns the file name and contents of the report
+    pub fn unzip(&self) -> KrakenResult<(String, Vec<u8>)> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(&self.data))?;
+        let mut file = archive.by_index(0)?;
+        let mut contents = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut contents)
+            .map_err(|err| KrakenErrors(vec![KError::ZipError(err.into())]))?;
+        Ok((file.name().to_string(), contents))
+    }
+
+    /// Parse a trades report into (trade ID, trade info) rows, in the order they appear in the
+    /// report
+    pub fn trades(&self) -> KrakenResult<Vec<(String, KOTradeData)>> {
+        self.rows::<TradeRow>()?
+            .into_iter()
+            .map(|row| {
+                let time = parse_time(&row.time)?;
+                Ok((
+                    row.txid,
+                    KOTradeData {
+                        ordertxid: row.ordertxid,
+                        pair: row.pair,
+                        time,
+                        tradetype: row.tradetype,
+                        ordertype: row.ordertype,
+                        price: row.price,
+                        cost: row.cost,
+                        fee: row.fee,
+                        vol: row.vol,
+                        margin: row.margin,
+                        misc: row.misc.unwrap_or_default(),
+                        posstatus: row.posstatus,
+                        cprice: None,
+                        cfee: None,
+                        cvol: None,
+                        cmargin: None,
+                        net: row.net,
+                        trades: None,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Parse a ledgers report into (ledger ID, ledger info) rows, in the order they appear in the
+    /// report
+    pub fn ledgers(&self) -> KrakenResult<Vec<(String, KOLedgerInfo)>> {
+        self.rows::<LedgerRow>()?
+            .into_iter()
+            .map(|row| {
+                let time = parse_time(&row.time)?;
+                Ok((
+                    row.txid,
+                    KOLedgerInfo {
+                        refid: row.refid,
+                        time,
+                        ledgertype: row.ledgertype,
+                        aclass: row.aclass,
+                        asset: row.asset,
+                        amount: row.amount,
+                        fee: row.fee,
+                        balance: row.balance,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    // Deserialize every row of the report. Reports requested in TSV format are detected by their
+    // file extension
+    fn rows<T>(&self) -> KrakenResult<Vec<T>>
+    where
+        for<'de> T: Deserialize<'de>,
+    {
+        let (name, contents) = self.unzip()?;
+        let delimiter = if name.to_lowercase().ends_with(".tsv") {
+            b'\t'
+        } else {
+            b','
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .from_reader(contents.as_slice());
+        let rows = reader
+            .deserialize()
+            .collect::<Result<Vec<T>, csv::Error>>()?;
+        Ok(rows)
+    }
+}
+
+#[derive(Deserialize)]
+struct TradeRow {
+    txid: String,
+    ordertxid: String,
+    pair: String,
+    time: String,
+    #[serde(rename = "type")]
+    tradetype: String,
+    ordertype: String,
+    price: KDecimal,
+    cost: KDecimal,
+    fee: KDecimal,
+    vol: KDecimal,
+    margin: Option<KDecimal>,
+    misc: Option<String>,
+    posstatus: Option<String>,
+    net: Option<KDecimal>,
+}
+
+#[derive(Deserialize)]
+struct LedgerRow {
+    txid: String,
+    refid: String,
+    time: String,
+    #[serde(rename = "type")]
+    ledgertype: String,
+    aclass: String,
+    asset: String,
+    amount: KDecimal,
+    fee: KDecimal,
+    balance: Option<KDecimal>,
+}
+
+// Reports contain UTC times formatted as "2021-04-01 00:18:45.1234". Convert them into the Unix
+// timestamps used by the JSON outputs
+fn parse_time(time: &str) -> KrakenResult<KTimestamp> {
+    let invalid = || {
+        KrakenErrors(vec![KError::ParseError(
+            <serde_json::Error as serde::de::Error>::custom(format!("invalid time: {}", time)),
+        )])
+    };
+    parse_secs(time, &invalid).and_then(|secs| timestamp_from_secs(secs).ok_or_else(invalid))
+}
+
+// Seconds since epoch of a report time
+fn parse_secs<F>(time: &str, invalid: &F) -> KrakenResult<f64>
+where
+    F: Fn() -> KrakenErrors<KError>,
+{
+    if let Ok(timestamp) = time.parse::<f64>() {
+        return Ok(timestamp);
+    }
+
+    let (date, clock) = time.trim().split_once(' ').ok_or_else(invalid)?;
+    let date = date
+        .split('-')
+        .map(|val| val.parse::<i64>().map_err(|_| invalid()))
+        .collect::<KrakenResult<Vec<i64>>>()?;
+    let (hours, rest) = clock.split_once(':').ok_or_else(invalid)?;
+    let (minutes, seconds) = rest.split_once(':').ok_or_else(invalid)?;
+    let hours = hours.parse::<i64>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<i64>().map_err(|_| invalid())?;
+    let seconds = seconds.parse::<f64>().map_err(|_| invalid())?;
+
+    match date[..] {
+        [year, month, day] => {
+            let days = days_from_civil(year, month, day);
+            Ok((days * 86400 + hours * 3600 + minutes * 60) as f64 + seconds)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+// Number of days since 1970-01-01 of a proleptic Gregorian calendar date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::timestamp_secs;
+    use std::io::Write;
+
+    fn archive(name: &str, contents: &str) -> KORetrieveExport {
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buf);
+            zip.start_file(name, zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        }
+        KORetrieveExport::from_bytes(buf.into_inner())
+    }
+
+    #[test]
+    fn parse_reports() {
+        let export = archive(
+            "trades.csv",
+            "\"txid\",\"ordertxid\",\"pair\",\"time\",\"type\",\"ordertype\",\"price\",\"cost\",\"fee\",\"vol\",\"margin\",\"misc\",\"ledgers\"\n\
+             \"TXID-1\",\"ORDER-1\",\"XXBTZUSD\",\"2021-04-01 00:18:45.5\",\"buy\",\"limit\",\"58000.0\",\"580.0\",\"1.5\",\"0.01\",\"0.0\",\"\",\"L-1,L-2\"\n",
+        );
+        let trades = export.trades().unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].0, "TXID-1");
+        assert_eq!(trades[0].1.tradetype, "buy");
+        assert!((timestamp_secs(&trades[0].1.time) - 1617236325.5).abs() < 1e-6);
+
+        let export = archive(
+            "ledgers.tsv",
+            "txid\trefid\ttime\ttype\tsubtype\taclass\tasset\tamount\tfee\tbalance\n\
+             L-1\tREF-1\t1970-01-02 00:00:00\tdeposit\t\tcurrency\tZUSD\t100.0\t0.0\t100.0\n",
+        );
+        let ledgers = export.ledgers().unwrap();
+        assert_eq!(ledgers[0].1.asset, "ZUSD");
+        assert!((timestamp_secs(&ledgers[0].1.time) - 86400.0).abs() < 1e-6);
+    }
+}
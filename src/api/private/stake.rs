@@ -0,0 +1,92 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Stake Asset endpoint
+pub struct KIStake {
+    params: IndexMap<String, String>,
+}
+
+impl KIStake {
+    /// Constructor returning a [KrakenInput] builder for the stake asset endpoint.
+    /// asset is the asset to stake, amount is the amount of the asset to stake and method is the
+    /// name of the staking option as returned by the
+    /// [List of Stakeable Assets][super::staking_assets::KIStakingAssets] endpoint
+    pub fn build(asset: KAsset, amount: f64, method: String) -> KIStake {
+        let stake = KIStake {
+            params: IndexMap::new(),
+        };
+        stake
+            .with_asset(asset)
+            .with_amount(amount)
+            .with_method(method)
+    }
+
+    /// Update the asset to stake
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the amount of the asset to stake
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    /// Update the name of the staking option to use
+    pub fn with_method(self, method: String) -> Self {
+        self.update_input("method", method)
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIStake {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIStake {}
+
+impl Input for KIStake {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("Stake"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("Stake"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Stake Asset endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStake {
+    /// Reference ID of the staking transaction
+    pub refid: String,
+}
+
+impl Output for KOStake {}
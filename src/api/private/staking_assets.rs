@@ -0,0 +1,139 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the List of Stakeable Assets endpoint
+pub struct KIStakingAssets {
+    params: IndexMap<String, String>,
+}
+
+impl KIStakingAssets {
+    /// Constructor returning a [KrakenInput] builder for the list of stakeable assets endpoint.
+    /// There are no inputs to this endpoint so finish() is called for you
+    pub fn build() -> KrakenInput {
+        let assets = KIStakingAssets {
+            params: IndexMap::new(),
+        };
+        assets.finish()
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIStakingAssets {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIStakingAssets {}
+
+impl Input for KIStakingAssets {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("Staking/Assets"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("Staking/Assets"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Staking reward rate | See [KOStakingAsset]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStakingReward {
+    /// Reward earned while staking
+    pub reward: String,
+    /// Type of reward (i.e. percentage)
+    #[serde(rename = "type")]
+    pub rewardtype: String,
+}
+
+/// Minimum amounts for staking and unstaking | See [KOStakingAsset]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStakingMinimum {
+    /// Minimum amount that can be staked
+    pub staking: String,
+    /// Minimum amount that can be unstaked
+    pub unstaking: String,
+}
+
+/// Lock period of staked or unstaked funds | See [KOStakingLocks]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStakingLockPeriod {
+    /// Number of days the funds are locked for
+    pub days: f64,
+    /// Percentage of the funds that are locked
+    pub percentage: f64,
+}
+
+/// Lock periods applied when staking and unstaking | See [KOStakingAsset]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStakingLocks {
+    /// Lock periods after requesting to stake
+    pub staking: Option<Vec<KOStakingLockPeriod>>,
+    /// Lock periods after requesting to unstake
+    pub unstaking: Option<Vec<KOStakingLockPeriod>>,
+    /// Lock periods during which the staked funds cannot be unstaked
+    pub lockup: Option<Vec<KOStakingLockPeriod>>,
+}
+
+/// Stakeable asset info | See [KOStakingAssets]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOStakingAsset {
+    /// Unique ID of the staking option, used as the method when staking
+    pub method: String,
+    /// Asset code of the asset that can be staked
+    pub asset: String,
+    /// Asset code of the staked asset (i.e. DOT.S)
+    pub staking_asset: String,
+    /// Staking reward rate
+    pub rewards: KOStakingReward,
+    /// Whether the staking operation is on-chain or not
+    pub on_chain: Option<bool>,
+    /// Whether the user will be able to stake this asset
+    pub can_stake: Option<bool>,
+    /// Whether the user will be able to unstake this asset
+    pub can_unstake: Option<bool>,
+    /// Minimum amounts for staking and unstaking
+    pub minimum_amount: Option<KOStakingMinimum>,
+    /// Lock periods applied when staking and unstaking
+    pub lock: Option<KOStakingLocks>,
+    /// Whether staking is enabled for the user
+    pub enabled_for_user: Option<bool>,
+    /// Whether staking of this asset is currently disabled
+    pub disabled: Option<bool>,
+}
+
+/// Response from the List of Stakeable Assets endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOStakingAssets {
+    /// List of assets the user can stake
+    pub assets: Vec<KOStakingAsset>,
+}
+
+impl Output for KOStakingAssets {}
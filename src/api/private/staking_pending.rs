@@ -0,0 +1,73 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KOStakingTransaction, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Pending Staking Transactions endpoint
+pub struct KIStakingPending {
+    params: IndexMap<String, String>,
+}
+
+impl KIStakingPending {
+    /// Constructor returning a [KrakenInput] builder for the get pending staking transactions endpoint.
+    /// There are no inputs to this endpoint so finish() is called for you
+    pub fn build() -> KrakenInput {
+        let pending = KIStakingPending {
+            params: IndexMap::new(),
+        };
+        pending.finish()
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIStakingPending {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIStakingPending {}
+
+impl Input for KIStakingPending {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("Staking/Pending"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("Staking/Pending"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Get Pending Staking Transactions endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOStakingPending {
+    /// List of staking transactions that have not completed yet
+    pub transactions: Vec<KOStakingTransaction>,
+}
+
+impl Output for KOStakingPending {}
@@ -0,0 +1,73 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KOStakingTransaction, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the List of Staking Transactions endpoint
+pub struct KIStakingTransactions {
+    params: IndexMap<String, String>,
+}
+
+impl KIStakingTransactions {
+    /// Constructor returning a [KrakenInput] builder for the list of staking transactions endpoint.
+    /// There are no inputs to this endpoint so finish() is called for you
+    pub fn build() -> KrakenInput {
+        let transactions = KIStakingTransactions {
+            params: IndexMap::new(),
+        };
+        transactions.finish()
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIStakingTransactions {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIStakingTransactions {}
+
+impl Input for KIStakingTransactions {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("Staking/Transactions"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("Staking/Transactions"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the List of Staking Transactions endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOStakingTransactions {
+    /// List of recent staking transactions
+    pub transactions: Vec<KOStakingTransaction>,
+}
+
+impl Output for KOStakingTransactions {}
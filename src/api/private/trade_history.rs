@@ -43,11 +43,23 @@ impl KITradeHistory {
         self.update_input("start", timestamp.to_string())
     }
 
+    /// Starting time to filter output by. Exclusive. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn starting_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("start", time.timestamp().to_string())
+    }
+
     /// Ending Unix timestamp to filter output by. Inclusive
     pub fn ending_timestamp(self, timestamp: u64) -> Self {
         self.update_input("end", timestamp.to_string())
     }
 
+    /// Ending time to filter output by. Inclusive. Requires the `timestamps` feature
+    #[cfg(feature = "timestamps")]
+    pub fn ending_datetime(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+        self.update_input("end", time.timestamp().to_string())
+    }
+
     /// Starting transaction ID to filter output by. Exclusive
     pub fn starting_txid(self, txid: String) -> Self {
         self.update_input("start", txid)
@@ -105,6 +117,9 @@ impl UpdateInput for KITradeHistory {}
 /// Response from the Get Trades History endpoint
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOTradeHistory {
+    /// Map with the trade ID as the key and the trade info as the value. Kraken sends these
+    /// under `trades`
+    #[serde(rename = "trades")]
     pub closed: HashMap<String, KOTradeData>,
     pub count: u32,
 }
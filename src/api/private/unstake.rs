@@ -0,0 +1,83 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Unstake Asset endpoint
+pub struct KIUnstake {
+    params: IndexMap<String, String>,
+}
+
+impl KIUnstake {
+    /// Constructor returning a [KrakenInput] builder for the unstake asset endpoint.
+    /// asset is the staked asset to unstake (i.e. [KAsset::DotS]) and amount is the amount of the
+    /// asset to unstake
+    pub fn build(asset: KAsset, amount: f64) -> KIUnstake {
+        let unstake = KIUnstake {
+            params: IndexMap::new(),
+        };
+        unstake.with_asset(asset).with_amount(amount)
+    }
+
+    /// Update the staked asset to unstake
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the amount of the asset to unstake
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIUnstake {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIUnstake {}
+
+impl Input for KIUnstake {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("Unstake"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("Unstake"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Unstake Asset endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOUnstake {
+    /// Reference ID of the unstaking transaction
+    pub refid: String,
+}
+
+impl Output for KOUnstake {}
@@ -0,0 +1,86 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Request Wallet Transfer endpoint
+pub struct KIWalletTransfer {
+    params: IndexMap<String, String>,
+}
+
+impl KIWalletTransfer {
+    /// Constructor returning a [KrakenInput] builder for the request wallet transfer endpoint.
+    /// Transfers `amount` of `asset` from the Spot Wallet to the Futures Wallet
+    pub fn build(asset: KAsset, amount: f64) -> KIWalletTransfer {
+        let transfer = KIWalletTransfer {
+            params: IndexMap::new(),
+        };
+        transfer
+            .with_asset(asset)
+            .with_amount(amount)
+            .update_input("from", String::from("Spot%20Wallet"))
+            .update_input("to", String::from("Futures%20Wallet"))
+    }
+
+    /// Update the asset to transfer
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the amount to transfer
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIWalletTransfer {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIWalletTransfer {}
+
+impl Input for KIWalletTransfer {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("WalletTransfer"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("WalletTransfer"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Request Wallet Transfer endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWalletTransfer {
+    /// Reference ID of the transfer
+    pub refid: String,
+}
+
+impl Output for KOWalletTransfer {}
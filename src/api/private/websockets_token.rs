@@ -0,0 +1,75 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Websockets Token endpoint
+pub struct KIWebSocketsToken {
+    params: IndexMap<String, String>,
+}
+
+impl KIWebSocketsToken {
+    /// Constructor returning a [KrakenInput] builder for the get websockets token endpoint.
+    /// There are no inputs to this endpoint so finish() is called for you
+    pub fn build() -> KrakenInput {
+        let token = KIWebSocketsToken {
+            params: IndexMap::new(),
+        };
+        token.finish()
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl Input for KIWebSocketsToken {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("GetWebSocketsToken"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("GetWebSocketsToken"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+impl MutateInput for KIWebSocketsToken {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIWebSocketsToken {}
+
+/// Response from the Get Websockets Token endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWebSocketsToken {
+    /// Token used to authenticate private websocket subscriptions
+    pub token: String,
+    /// Time (in seconds) after which the token expires if no subscription is made with it. Once a
+    /// subscription is made the token does not expire
+    pub expires: u32,
+}
+
+impl Output for KOWebSocketsToken {}
@@ -0,0 +1,100 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Withdraw Funds endpoint
+pub struct KIWithdraw {
+    params: IndexMap<String, String>,
+}
+
+impl KIWithdraw {
+    /// Constructor returning a [KrakenInput] builder for the withdraw funds endpoint.
+    /// asset is the asset being withdrawn, key is the withdrawal key name as set up on the
+    /// account and amount is the amount to be withdrawn. The fee and limits of a withdrawal can
+    /// be previewed beforehand with the [Get Withdrawal Information][super::withdraw_info::KIWithdrawInfo]
+    /// endpoint
+    pub fn build(asset: KAsset, key: String, amount: f64) -> KIWithdraw {
+        let withdraw = KIWithdraw {
+            params: IndexMap::new(),
+        };
+        withdraw.with_asset(asset).with_key(key).with_amount(amount)
+    }
+
+    /// Update the asset being withdrawn
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the withdrawal key name, as set up on the account
+    pub fn with_key(self, key: String) -> Self {
+        self.update_input("key", key)
+    }
+
+    /// Update the amount to be withdrawn
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    /// Crypto address that must match the address of the withdrawal key, used as a safety check
+    pub fn with_address(self, address: String) -> Self {
+        self.update_input("address", address)
+    }
+
+    /// Withdrawal fails if the fee Kraken charges is higher than max_fee
+    pub fn with_max_fee(self, max_fee: f64) -> Self {
+        self.update_input("max_fee", max_fee.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIWithdraw {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIWithdraw {}
+
+impl Input for KIWithdraw {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("Withdraw"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("Withdraw"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Withdraw Funds endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWithdraw {
+    /// Reference ID of the withdrawal
+    pub refid: String,
+}
+
+impl Output for KOWithdraw {}
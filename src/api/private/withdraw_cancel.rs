@@ -0,0 +1,84 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Request Withdrawal Cancelation endpoint
+pub struct KIWithdrawCancel {
+    params: IndexMap<String, String>,
+}
+
+impl KIWithdrawCancel {
+    /// Constructor returning a [KrakenInput] builder for the request withdrawal cancelation
+    /// endpoint. asset is the asset being withdrawn and refid is the reference ID returned by the
+    /// [Withdraw Funds][super::withdraw::KIWithdraw] endpoint
+    pub fn build(asset: KAsset, refid: String) -> KIWithdrawCancel {
+        let cancel = KIWithdrawCancel {
+            params: IndexMap::new(),
+        };
+        cancel.with_asset(asset).with_refid(refid)
+    }
+
+    /// Update the asset being withdrawn
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the reference ID of the withdrawal to cancel
+    pub fn with_refid(self, refid: String) -> Self {
+        self.update_input("refid", refid)
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIWithdrawCancel {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIWithdrawCancel {}
+
+impl Input for KIWithdrawCancel {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("WithdrawCancel"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("WithdrawCancel"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Request Withdrawal Cancelation endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOWithdrawCancel {
+    /// Whether cancelation was successful or not. Cancelation cannot be guaranteed
+    pub success: bool,
+}
+
+impl Output for KOWithdrawCancel {}
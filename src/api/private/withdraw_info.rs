@@ -0,0 +1,95 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Withdrawal Information endpoint
+pub struct KIWithdrawInfo {
+    params: IndexMap<String, String>,
+}
+
+impl KIWithdrawInfo {
+    /// Constructor returning a [KrakenInput] builder for the get withdrawal information endpoint.
+    /// asset is the asset being withdrawn, key is the withdrawal key name as set up on the
+    /// account and amount is the amount to be withdrawn
+    pub fn build(asset: KAsset, key: String, amount: f64) -> KIWithdrawInfo {
+        let info = KIWithdrawInfo {
+            params: IndexMap::new(),
+        };
+        info.with_asset(asset).with_key(key).with_amount(amount)
+    }
+
+    /// Update the asset being withdrawn
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Update the withdrawal key name, as set up on the account
+    pub fn with_key(self, key: String) -> Self {
+        self.update_input("key", key)
+    }
+
+    /// Update the amount to be withdrawn
+    pub fn with_amount(self, amount: f64) -> Self {
+        self.update_input("amount", amount.to_string())
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIWithdrawInfo {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIWithdrawInfo {}
+
+impl Input for KIWithdrawInfo {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("WithdrawInfo"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("WithdrawInfo"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Response from the Get Withdrawal Information endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWithdrawInfo {
+    /// Name of the withdrawal method that will be used
+    pub method: String,
+    /// Maximum net amount that can be withdrawn right now (None if there is no limit)
+    #[serde(deserialize_with = "super::deserialize_funding_limit")]
+    pub limit: Option<String>,
+    /// Net amount that will be sent, after fees
+    pub amount: String,
+    /// Amount of fees that will be paid
+    pub fee: String,
+}
+
+impl Output for KOWithdrawInfo {}
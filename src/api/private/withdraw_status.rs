@@ -0,0 +1,111 @@
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::KrakenAuth;
+// Structs/Enums
+use super::{EndpointInfo, KAsset, KOFundingStatus, KOFundingStatusProp, KrakenInput, MethodType};
+
+// Traits
+use super::{Input, MutateInput, Output, UpdateInput};
+
+/// Request builder for the Get Status of Recent Withdrawals endpoint
+pub struct KIWithdrawStatus {
+    params: IndexMap<String, String>,
+}
+
+impl KIWithdrawStatus {
+    /// Constructor returning a [KrakenInput] builder for the get status of recent withdrawals
+    /// endpoint. asset is the asset being withdrawn
+    pub fn build(asset: KAsset) -> KIWithdrawStatus {
+        let status = KIWithdrawStatus {
+            params: IndexMap::new(),
+        };
+        status.with_asset(asset)
+    }
+
+    /// Update the asset being withdrawn
+    pub fn with_asset(self, asset: KAsset) -> Self {
+        self.update_input("asset", asset.to_string())
+    }
+
+    /// Filter for a specific name of withdrawal method
+    pub fn with_method(self, method: String) -> Self {
+        self.update_input("method", method)
+    }
+
+    fn with_nonce(self) -> Self {
+        self.update_input("nonce", KrakenAuth::nonce())
+    }
+}
+
+impl MutateInput for KIWithdrawStatus {
+    fn list_mut(&mut self) -> &mut IndexMap<String, String> {
+        &mut self.params
+    }
+}
+
+impl UpdateInput for KIWithdrawStatus {}
+
+impl Input for KIWithdrawStatus {
+    fn finish(self) -> KrakenInput {
+        KrakenInput {
+            info: EndpointInfo {
+                methodtype: MethodType::Private,
+                endpoint: String::from("WithdrawStatus"),
+            },
+            params: Some(self.with_nonce().params),
+        }
+    }
+
+    fn finish_clone(self) -> (KrakenInput, Self) {
+        let newself = self.with_nonce();
+        (
+            KrakenInput {
+                info: EndpointInfo {
+                    methodtype: MethodType::Private,
+                    endpoint: String::from("WithdrawStatus"),
+                },
+                params: Some(newself.params.clone()),
+            },
+            newself,
+        )
+    }
+}
+
+/// Withdrawal status info | See [KOWithdrawStatus]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWithdrawalInfo {
+    /// Name of withdrawal method
+    pub method: String,
+    /// Asset class
+    pub aclass: String,
+    /// Asset
+    pub asset: String,
+    /// Reference ID
+    pub refid: String,
+    /// Method transaction ID
+    pub txid: Option<String>,
+    /// Method transaction information
+    pub info: String,
+    /// Amount withdrawn
+    pub amount: String,
+    /// Fees paid
+    pub fee: String,
+    /// Unix timestamp when request was made
+    pub time: u64,
+    /// Status of withdrawal
+    pub status: KOFundingStatus,
+    /// Additional status properties (if available)
+    #[serde(rename = "status-prop")]
+    pub status_prop: Option<KOFundingStatusProp>,
+}
+
+/// Response from the Get Status of Recent Withdrawals endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(transparent)]
+pub struct KOWithdrawStatus {
+    /// List of recent withdrawals for the requested asset
+    pub withdrawals: Vec<KOWithdrawalInfo>,
+}
+
+impl Output for KOWithdrawStatus {}
@@ -8,8 +8,9 @@
 // Structs/Enums
 use super::asset::{AssetPairInfo, KAsset, KAssetPair};
 use super::{
-    EndpointInfo, Input, InputList, InputListItem, IntoInputList, KrakenInput, MethodType,
-    MutateInput, OHLCInterval, Output, SystemStatus, UpdateInput,
+    EndpointInfo, Input, InputList, InputListItem, IntoInputList, KDateTime, KDecimal,
+    KTimestamp, KUnixTime, KrakenInput, MethodType, MutateInput, OHLCInterval, Output,
+    SystemStatus, UpdateInput,
 };
 
 /// Get server time endpoint
@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
-    EndpointInfo, Input, KAssetPair, KrakenInput, MethodType, MutateInput, OHLCInterval, Output,
-    UpdateInput,
+    EndpointInfo, Input, KAssetPair, KUnixTime, KrakenInput, MethodType, MutateInput,
+    OHLCInterval, Output, UpdateInput,
 };
 
 /// Request builder for the Get OHLC Data endpoint
@@ -76,7 +76,8 @@ impl UpdateInput for KIOHLC {}
 /// OHLC info data
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOOHLCData {
-    pub timestamp: i64,
+    #[serde(deserialize_with = "crate::api::deserialize_seconds")]
+    pub timestamp: KUnixTime,
     pub open: String,
     pub high: String,
     pub low: String,
@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
-    EndpointInfo, Input, KAssetPair, KrakenInput, MethodType, MutateInput, Output, UpdateInput,
+    EndpointInfo, Input, KAssetPair, KDecimal, KrakenInput, MethodType, MutateInput, Output,
+    UpdateInput,
 };
 
 /// Request builder for the Get Order Book endpoint
@@ -68,8 +69,8 @@ impl UpdateInput for KIOrderBook {}
 /// Order book data
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOOrderBookData {
-    pub price: String,
-    pub volume: String,
+    pub price: KDecimal,
+    pub volume: KDecimal,
     pub timestamp: i64,
 }
 
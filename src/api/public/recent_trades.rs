@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
-    EndpointInfo, Input, KAssetPair, KrakenInput, MethodType, MutateInput, Output, UpdateInput,
+    EndpointInfo, Input, KAssetPair, KTimestamp, KrakenInput, MethodType, MutateInput, Output,
+    UpdateInput,
 };
 
 /// Request builder for the Get Recent Trades endpoint
@@ -71,7 +72,8 @@ impl UpdateInput for KIRecentTrades {}
 pub struct KOTradeInfo {
     pub price: String,
     pub volume: String,
-    pub time: f64,
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub time: KTimestamp,
     pub tradetype: String,
     pub ordertype: String,
     pub misc: String,
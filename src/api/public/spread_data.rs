@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
-    EndpointInfo, Input, KAssetPair, KrakenInput, MethodType, MutateInput, Output, UpdateInput,
+    EndpointInfo, Input, KAssetPair, KUnixTime, KrakenInput, MethodType, MutateInput, Output,
+    UpdateInput,
 };
 
 /// Request builder for the Get Recent Spread Data endpoint
@@ -69,7 +70,8 @@ impl UpdateInput for KISpreadData {}
 /// Spread info
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOSpreadInfo {
-    pub time: i64,
+    #[serde(deserialize_with = "crate::api::deserialize_seconds")]
+    pub time: KUnixTime,
     pub bid: String,
     pub ask: String,
 }
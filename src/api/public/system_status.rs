@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{EndpointInfo, Input, KrakenInput, MethodType, Output, SystemStatus};
+use super::{EndpointInfo, Input, KDateTime, KrakenInput, MethodType, Output, SystemStatus};
 
 /// Request builder for the Get System Status endpoint
 pub struct KISystemStatus();
@@ -45,7 +45,7 @@ pub struct KOSystemStatus {
     /// Current system status or trading mode
     pub status: SystemStatus,
     /// Server time
-    pub timestamp: String,
+    pub timestamp: KDateTime,
 }
 
 impl Output for KOSystemStatus {}
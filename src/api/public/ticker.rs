@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
-    EndpointInfo, Input, InputList, InputListItem, IntoInputList, KAssetPair, KrakenInput,
-    MethodType, MutateInput, Output, UpdateInput,
+    EndpointInfo, Input, InputList, InputListItem, IntoInputList, KAssetPair, KDecimal,
+    KrakenInput, MethodType, MutateInput, Output, UpdateInput,
 };
 
 /// Request builder for the Get Ticker Information endpoint
@@ -94,23 +94,23 @@ impl InputList for KITicker {}
 #[derive(Deserialize, Serialize, Debug)]
 pub struct KOTick {
     /// ask array(<price>, <whole lot volume>, <lot volume>)
-    pub a: Vec<String>,
+    pub a: Vec<KDecimal>,
     /// bid array(<price>, <whole lot volume>, <lot volume>)
-    pub b: Vec<String>,
+    pub b: Vec<KDecimal>,
     /// last trade closed array(<price>, <lot volume>)
-    pub c: Vec<String>,
+    pub c: Vec<KDecimal>,
     /// volume array(<today>, <last 24 hours>)
-    pub v: Vec<String>,
+    pub v: Vec<KDecimal>,
     /// volume weighted average price array(<today>, <last 24 hours>)
-    pub p: Vec<String>,
+    pub p: Vec<KDecimal>,
     /// number of trades array(<today>, <last 24 hours>)
     pub t: Vec<u32>,
     /// low array(<today>, <last 24 hours>)
-    pub l: Vec<String>,
+    pub l: Vec<KDecimal>,
     /// high array(<today>, <last 24 hours>)
-    pub h: Vec<String>,
+    pub h: Vec<KDecimal>,
     /// today's opening price
-    pub o: String,
+    pub o: KDecimal,
 }
 
 /// Response from the Get Ticker Information endpoint
@@ -26,13 +26,15 @@ impl KrakenAuth {
         &self.api_secret
     }
 
+    // Placeholder nonce set by the input builders. The client replaces it with a nonce from its
+    // NonceSource before signing the request
     pub(crate) fn nonce() -> String {
         let duration = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap();
         let nonce = (duration.as_secs() * 1_000_000u64) + u64::from(duration.subsec_micros());
 
-        (nonce as u64).to_string()
+        nonce.to_string()
     }
 
     pub(crate) fn sign(&self, path: &str, nonce: &str, params: &str) -> String {
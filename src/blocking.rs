@@ -0,0 +1,204 @@
+//! Blocking HTTP client for use without an async runtime. Requires the `blocking` feature
+//!
+//! # Note
+//! [KrakenClient] wraps an asynchronous [AsyncClient] together with a single threaded tokio
+//! runtime it owns, and blocks the calling thread until each request completes. The runtime is
+//! driven by a background thread, so pooled connections are kept alive and closed connections
+//! cleaned up between requests. The thread stops when the client is dropped. Everything
+//! configurable on the asynchronous client (additional accounts, rate limiting, retries,
+//! timeouts, recording) is reached through [client_mut()][KrakenClient::client_mut].
+//!
+//! The blocking client must not be used from within an async runtime, where blocking the thread
+//! panics. Use the [asynchronous client][AsyncClient] there instead
+//!
+//! ```no_run
+//! use kraapi::blocking::KrakenClient;
+//! use kraapi::public::server_time::{KIServerTime, KOServerTime};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KrakenClient::new("", "")?;
+//! let time = client.request::<KOServerTime>(&KIServerTime::build())?;
+//! println!("{}", time.rfc1123);
+//! # Ok(())
+//! # }
+//! ```
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::io;
+use std::thread::{self, JoinHandle};
+use tokio::runtime::{Builder, Handle};
+use tokio::sync::oneshot;
+
+use crate::api::{BinaryOutput, KrakenInput, KrakenResult, Output};
+use crate::client::KrakenClient as AsyncClient;
+use crate::timeout::Timeouts;
+use crate::transport::Transport;
+
+/// Blocking HTTP client sending instances of [KrakenInput] to the Kraken servers | See the
+/// [module documentation][self]
+pub struct KrakenClient {
+    client: AsyncClient,
+    runtime: RuntimeThread,
+}
+
+// Runtime driven by a background thread until dropped
+struct RuntimeThread {
+    handle: Handle,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RuntimeThread {
+    fn start() -> io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let handle = runtime.handle().clone();
+        let (shutdown, stopped) = oneshot::channel();
+        let thread = thread::Builder::new()
+            .name(String::from("kraapi-blocking"))
+            .spawn(move || {
+                let _ = runtime.block_on(stopped);
+            })?;
+
+        Ok(RuntimeThread {
+            handle,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    // Run `future` to completion on the calling thread while the background thread drives the
+    // IO and timers it waits on
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.handle.block_on(future)
+    }
+}
+
+impl Drop for RuntimeThread {
+    fn drop(&mut self) {
+        drop(self.shutdown.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl KrakenClient {
+    /// Construct a new blocking client. See [AsyncClient::new()] for the requirements on the
+    /// credentials. Fails if the internal runtime can't be started
+    pub fn new(key: &str, secret: &str) -> io::Result<Self> {
+        KrakenClient::from_client(AsyncClient::new(key, secret))
+    }
+
+    /// Construct a new blocking client sending its requests through `transport`. See
+    /// [AsyncClient::with_transport()]
+    pub fn with_transport<T>(key: &str, secret: &str, transport: T) -> io::Result<Self>
+    where
+        T: Transport + 'static,
+    {
+        KrakenClient::from_client(AsyncClient::with_transport(key, secret, transport))
+    }
+
+    /// Construct a blocking client around an already configured asynchronous client
+    pub fn from_client(client: AsyncClient) -> io::Result<Self> {
+        Ok(KrakenClient {
+            client,
+            runtime: RuntimeThread::start()?,
+        })
+    }
+
+    /// Returns the wrapped asynchronous client
+    pub fn client(&self) -> &AsyncClient {
+        &self.client
+    }
+
+    /// Returns the wrapped asynchronous client to change its configuration
+    pub fn client_mut(&mut self) -> &mut AsyncClient {
+        &mut self.client
+    }
+
+    /// Unwrap the asynchronous client, stopping the internal runtime
+    pub fn into_client(self) -> AsyncClient {
+        self.client
+    }
+
+    /// Make a request to the desired API endpoint and block until it completes. See
+    /// [AsyncClient::request()]
+    pub fn request<T>(&self, input: &KrakenInput) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        self.runtime.block_on(self.client.request(input))
+    }
+
+    /// Make a request with the credentials registered under `account`. See
+    /// [AsyncClient::request_as()]
+    pub fn request_as<T>(&self, account: &str, input: &KrakenInput) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        self.runtime
+            .block_on(self.client.request_as(account, input))
+    }
+
+    /// Make a request with `timeouts` overriding the client's timeouts for this call. See
+    /// [AsyncClient::request_with_timeouts()]
+    pub fn request_with_timeouts<T>(
+        &self,
+        input: &KrakenInput,
+        timeouts: &Timeouts,
+    ) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        self.runtime
+            .block_on(self.client.request_with_timeouts(input, timeouts))
+    }
+
+    /// Make a request to an API endpoint that responds with a file instead of JSON. See
+    /// [AsyncClient::request_binary()]
+    pub fn request_binary<T>(&self, input: &KrakenInput) -> KrakenResult<T>
+    where
+        T: BinaryOutput,
+    {
+        self.runtime.block_on(self.client.request_binary(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::public::server_time::{KIServerTime, KOServerTime};
+    use crate::retry::RetryPolicy;
+    use crate::transport::MockTransport;
+    use std::time::Duration;
+
+    #[test]
+    fn blocking_request() {
+        let mock = MockTransport::new()
+            .with_errors("Time", &["EService:Unavailable"])
+            .with_result(
+                "Time",
+                serde_json::json!({ "unixtime": 1616336594, "rfc1123": "Sun, 21 Mar 21 14:23:14 +0000" }),
+            );
+        let mut client = KrakenClient::with_transport("", "", mock).unwrap();
+        client.client_mut().set_retry_policy(Some(
+            RetryPolicy::new().with_initial_backoff(Duration::from_millis(1)),
+        ));
+
+        let time = client
+            .request::<KOServerTime>(&KIServerTime::build())
+            .unwrap();
+        assert_eq!(time.unixtime, 1616336594);
+
+        // Tasks such as the connection pool's keep running between requests
+        let (sender, receiver) = std::sync::mpsc::channel();
+        client.runtime.handle.spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(()).unwrap();
+        });
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
@@ -0,0 +1,562 @@
+//! Locally maintained order book kept in sync with Kraken's book feeds
+//!
+//! # Note
+//! An [OrderBook] is seeded from the output of the
+//! [Get Order Book][crate::public::order_book::KIOrderBook] endpoint or a websocket book
+//! snapshot, and is then kept up to date by applying the incremental updates streamed on the
+//! websocket book channel. Every update carries Kraken's CRC32 checksum of the top 10 levels
+//! which is verified after the update is applied so a desynced book is detected immediately
+//!
+//! A desynced book is best recovered with
+//! [apply_or_resubscribe()][OrderBook::apply_or_resubscribe], which resubscribes to the book
+//! channel so Kraken publishes a new snapshot in sequence with the updates that follow it. The
+//! REST snapshot used by [apply_or_resync()][OrderBook::apply_or_resync] isn't sequenced with
+//! the websocket updates, so updates already sent by Kraken can be applied on top of a snapshot
+//! that includes them, failing the checksum again
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::api::asset::KAssetPair;
+use crate::api::public::order_book::{KIOrderBook, KOOrderBook, KOOrderDepthPair};
+use crate::api::{Input, KrakenResult};
+use crate::client::KrakenClient;
+use crate::error::{KError, KrakenErrors};
+use crate::ws::public::{BookDepth, Channel, KOWSBook, KOWSBookLevel, KWSPublic, KWSSubscription};
+
+// Number of levels per side covered by Kraken's book checksum
+const CHECKSUM_LEVELS: usize = 10;
+
+/// Side of the order book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// Single price level of the order book
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookLevel {
+    /// Price as sent by Kraken
+    pub price: String,
+    /// Volume as sent by Kraken
+    pub volume: String,
+    /// Time of the last update to this level, seconds since epoch
+    pub timestamp: String,
+}
+
+impl BookLevel {
+    /// Price of this level as a float
+    pub fn price_f64(&self) -> f64 {
+        self.price.parse().unwrap_or_default()
+    }
+
+    /// Volume of this level as a float
+    pub fn volume_f64(&self) -> f64 {
+        self.volume.parse().unwrap_or_default()
+    }
+
+    // Kraken's checksum input for a level: the price and volume with the decimal point and any
+    // leading zeros removed
+    fn checksum_input(&self) -> String {
+        let strip = |val: &str| {
+            let digits = val.replace('.', "");
+            digits.trim_start_matches('0').to_string()
+        };
+        format!("{}{}", strip(&self.price), strip(&self.volume))
+    }
+}
+
+// Exact ordering key for a decimal price string. Prices are compared on their integer part
+// first and then on the fractional digits padded to a fixed width, avoiding any float rounding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PriceKey(u64, u64);
+
+impl PriceKey {
+    const FRACTION_DIGITS: usize = 12;
+
+    fn parse(price: &str) -> Option<Self> {
+        let (int, frac) = match price.split_once('.') {
+            Some((int, frac)) => (int, frac),
+            None => (price, ""),
+        };
+        if frac.len() > PriceKey::FRACTION_DIGITS {
+            return None;
+        }
+        let int = if int.is_empty() { Ok(0) } else { int.parse() };
+        let frac = format!("{:0<width$}", frac, width = PriceKey::FRACTION_DIGITS).parse();
+        match (int, frac) {
+            (Ok(int), Ok(frac)) => Some(PriceKey(int, frac)),
+            _ => None,
+        }
+    }
+}
+
+fn is_zero(volume: &str) -> bool {
+    volume.chars().all(|c| c == '0' || c == '.')
+}
+
+/// Order book maintained locally from a snapshot and incremental updates
+///
+/// Levels are kept sorted (asks ascending, bids descending) and truncated to the depth the book
+/// was created with, which should match the depth of the websocket subscription
+pub struct OrderBook {
+    pair: KAssetPair,
+    depth: usize,
+    asks: BTreeMap<PriceKey, BookLevel>,
+    bids: BTreeMap<PriceKey, BookLevel>,
+    synced: bool,
+}
+
+impl OrderBook {
+    /// Construct an empty order book for `pair` holding at most `depth` levels per side. The
+    /// book is not synced until it has been seeded with a snapshot
+    pub fn new(pair: KAssetPair, depth: usize) -> Self {
+        OrderBook {
+            pair,
+            depth,
+            asks: BTreeMap::new(),
+            bids: BTreeMap::new(),
+            synced: false,
+        }
+    }
+
+    /// Construct an order book seeded from the output of the
+    /// [Get Order Book][crate::public::order_book::KIOrderBook] endpoint
+    pub fn from_snapshot(pair: KAssetPair, depth: usize, snapshot: &KOOrderDepthPair) -> Self {
+        let mut book = OrderBook::new(pair, depth);
+        book.seed(snapshot);
+        book
+    }
+
+    /// Replace the contents of the book with the output of the
+    /// [Get Order Book][crate::public::order_book::KIOrderBook] endpoint
+    pub fn seed(&mut self, snapshot: &KOOrderDepthPair) {
+        self.asks.clear();
+        self.bids.clear();
+        for level in snapshot.asks.iter() {
+            self.update_level(
+                BookSide::Ask,
+                &level.price.to_string(),
+                &level.volume.to_string(),
+                level.timestamp.to_string(),
+            );
+        }
+        for level in snapshot.bids.iter() {
+            self.update_level(
+                BookSide::Bid,
+                &level.price.to_string(),
+                &level.volume.to_string(),
+                level.timestamp.to_string(),
+            );
+        }
+        self.truncate();
+        self.synced = true;
+    }
+
+    /// Apply a websocket book message to the book
+    ///
+    /// Snapshots replace the contents of the book. Updates are applied level by level, the book
+    /// is truncated to its depth and Kraken's checksum is verified. On a checksum mismatch the
+    /// book is marked as out of sync, [KError::ChecksumMismatch] is returned, and further
+    /// updates are rejected until a new snapshot is applied or [resync()][OrderBook::resync]
+    /// is called
+    pub fn apply(&mut self, message: &KOWSBook) -> KrakenResult<()> {
+        if message.snapshot {
+            self.asks.clear();
+            self.bids.clear();
+            self.apply_levels(BookSide::Ask, &message.asks);
+            self.apply_levels(BookSide::Bid, &message.bids);
+            self.truncate();
+            self.synced = true;
+            return Ok(());
+        }
+
+        if !self.synced {
+            return Err(KrakenErrors(vec![KError::ChecksumMismatch]));
+        }
+
+        self.apply_levels(BookSide::Ask, &message.asks);
+        self.apply_levels(BookSide::Bid, &message.bids);
+        self.truncate();
+
+        if let Some(checksum) = &message.checksum {
+            if checksum.parse::<u32>().ok() != Some(self.checksum()) {
+                self.synced = false;
+                return Err(KrakenErrors(vec![KError::ChecksumMismatch]));
+            }
+        }
+        Ok(())
+    }
+
+    /// Request a new snapshot by unsubscribing from and resubscribing to the book channel of this
+    /// book's pair on `client`. The book is out of sync until the snapshot streamed by `client`
+    /// is applied. Fails with [KError::InvalidArguments] if the depth of the book isn't one of
+    /// the [BookDepth]s that can be subscribed to
+    pub async fn resubscribe(&mut self, client: &mut KWSPublic) -> KrakenResult<()> {
+        let depth = match self.depth {
+            10 => BookDepth::Ten,
+            25 => BookDepth::TwentyFive,
+            100 => BookDepth::OneHundred,
+            500 => BookDepth::FiveHundred,
+            1000 => BookDepth::OneThousand,
+            _ => return Err(KrakenErrors(vec![KError::InvalidArguments])),
+        };
+        let subscription = KWSSubscription::build(Channel::Book(depth), self.pair);
+        self.synced = false;
+        client.unsubscribe(&subscription).await?;
+        client.subscribe(&subscription).await
+    }
+
+    /// Apply a websocket book message, resubscribing to the book channel on `client` if the
+    /// checksum reveals the book is out of sync. Updates received until the new snapshot
+    /// arrives are dropped
+    pub async fn apply_or_resubscribe(
+        &mut self,
+        message: &KOWSBook,
+        client: &mut KWSPublic,
+    ) -> KrakenResult<()> {
+        if !self.synced && !message.snapshot {
+            return Ok(());
+        }
+        match self.apply(message) {
+            Err(KrakenErrors(errors)) if matches!(errors[..], [KError::ChecksumMismatch]) => {
+                self.resubscribe(client).await
+            }
+            result => result,
+        }
+    }
+
+    /// Re-seed the book from a fresh [Get Order Book][crate::public::order_book::KIOrderBook]
+    /// snapshot requested with `client`. The snapshot isn't sequenced with the websocket
+    /// updates, see the [module documentation][self]
+    pub async fn resync(&mut self, client: &KrakenClient) -> KrakenResult<()> {
+        let input = KIOrderBook::build(self.pair)
+            .with_max(self.depth as i64)
+            .finish();
+        let output = client.request::<KOOrderBook>(&input).await?;
+        // Kraken keys the response by its own pair name so take whichever entry was returned
+        match output.pair.into_iter().next() {
+            Some((_, snapshot)) => {
+                self.seed(&snapshot);
+                Ok(())
+            }
+            None => Err(KrakenErrors(vec![KError::UnknownAssetPair])),
+        }
+    }
+
+    /// Apply a websocket book message, automatically re-seeding the book with a REST snapshot
+    /// from `client` if the checksum reveals the book is out of sync. Prefer
+    /// [apply_or_resubscribe()][OrderBook::apply_or_resubscribe], as the REST snapshot can race
+    /// the websocket updates
+    pub async fn apply_or_resync(
+        &mut self,
+        message: &KOWSBook,
+        client: &KrakenClient,
+    ) -> KrakenResult<()> {
+        match self.apply(message) {
+            Err(KrakenErrors(errors)) if matches!(errors[..], [KError::ChecksumMismatch]) => {
+                self.resync(client).await
+            }
+            result => result,
+        }
+    }
+
+    /// Kraken's CRC32 checksum of the top 10 asks and bids currently in the book
+    pub fn checksum(&self) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        for level in self.asks().take(CHECKSUM_LEVELS) {
+            hasher.update(level.checksum_input().as_bytes());
+        }
+        for level in self.bids().take(CHECKSUM_LEVELS) {
+            hasher.update(level.checksum_input().as_bytes());
+        }
+        hasher.finalize()
+    }
+
+    /// Asset pair of this book
+    pub fn pair(&self) -> KAssetPair {
+        self.pair
+    }
+
+    /// Maximum number of levels kept per side
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether the book holds a snapshot and no checksum mismatch has been detected since
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Ask levels sorted from the best (lowest) price
+    pub fn asks(&self) -> impl Iterator<Item = &BookLevel> {
+        self.asks.values()
+    }
+
+    /// Bid levels sorted from the best (highest) price
+    pub fn bids(&self) -> impl Iterator<Item = &BookLevel> {
+        self.bids.values().rev()
+    }
+
+    /// Lowest ask
+    pub fn best_ask(&self) -> Option<&BookLevel> {
+        self.asks().next()
+    }
+
+    /// Highest bid
+    pub fn best_bid(&self) -> Option<&BookLevel> {
+        self.bids().next()
+    }
+
+    /// Midpoint between the best bid and the best ask
+    pub fn mid(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((bid.price_f64() + ask.price_f64()) / 2.0),
+            _ => None,
+        }
+    }
+
+    /// Difference between the best ask and the best bid
+    pub fn spread(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask.price_f64() - bid.price_f64()),
+            _ => None,
+        }
+    }
+
+    /// Volume resting at exactly `price` on the given side, if that level exists
+    pub fn depth_at_price(&self, side: BookSide, price: &str) -> Option<&BookLevel> {
+        let key = PriceKey::parse(price)?;
+        match side {
+            BookSide::Ask => self.asks.get(&key),
+            BookSide::Bid => self.bids.get(&key),
+        }
+    }
+
+    /// Total volume on the given side from the best price up to and including `price`. For
+    /// asks this is every level priced at or below `price`, for bids every level at or above it
+    pub fn cumulative_volume(&self, side: BookSide, price: &str) -> f64 {
+        let key = match PriceKey::parse(price) {
+            Some(key) => key,
+            None => return 0.0,
+        };
+        match side {
+            BookSide::Ask => self.asks.range(..=key).map(|(_, l)| l.volume_f64()).sum(),
+            BookSide::Bid => self.bids.range(key..).map(|(_, l)| l.volume_f64()).sum(),
+        }
+    }
+
+    fn apply_levels(&mut self, side: BookSide, levels: &[KOWSBookLevel]) {
+        for level in levels {
+            self.update_level(side, &level.price, &level.volume, level.timestamp.clone());
+        }
+    }
+
+    fn update_level(&mut self, side: BookSide, price: &str, volume: &str, timestamp: String) {
+        let key = match PriceKey::parse(price) {
+            Some(key) => key,
+            None => return,
+        };
+        let levels = match side {
+            BookSide::Ask => &mut self.asks,
+            BookSide::Bid => &mut self.bids,
+        };
+        if is_zero(volume) {
+            levels.remove(&key);
+        } else {
+            levels.insert(
+                key,
+                BookLevel {
+                    price: price.to_string(),
+                    volume: volume.to_string(),
+                    timestamp,
+                },
+            );
+        }
+    }
+
+    // Drop levels that fell outside the subscribed depth. Asks are dropped from the top of the
+    // map (highest prices) and bids from the bottom (lowest prices)
+    fn truncate(&mut self) {
+        while self.asks.len() > self.depth {
+            let worst = *self.asks.keys().next_back().unwrap();
+            self.asks.remove(&worst);
+        }
+        while self.bids.len() > self.depth {
+            let worst = *self.bids.keys().next().unwrap();
+            self.bids.remove(&worst);
+        }
+    }
+}
+
+impl fmt::Debug for OrderBook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrderBook")
+            .field("pair", &self.pair)
+            .field("synced", &self.synced)
+            .field("asks", &self.asks().collect::<Vec<&BookLevel>>())
+            .field("bids", &self.bids().collect::<Vec<&BookLevel>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::KAsset;
+    use crate::api::public::order_book::KOOrderBookData;
+
+    fn level(price: &str, volume: &str) -> KOWSBookLevel {
+        KOWSBookLevel {
+            price: price.to_string(),
+            volume: volume.to_string(),
+            timestamp: String::from("1534614248.123678"),
+            republish: false,
+        }
+    }
+
+    fn snapshot() -> KOOrderDepthPair {
+        let data = |price: &str, volume: &str| KOOrderBookData {
+            price: price.parse().unwrap(),
+            volume: volume.parse().unwrap(),
+            timestamp: 1534614248,
+        };
+        KOOrderDepthPair {
+            asks: vec![
+                data("5541.30000", "2.50700000"),
+                data("5541.80000", "0.33000000"),
+                data("5542.70000", "0.64700000"),
+            ],
+            bids: vec![
+                data("5541.20000", "1.52900000"),
+                data("5539.90000", "0.30000000"),
+                data("5539.50000", "5.00000000"),
+            ],
+        }
+    }
+
+    #[test]
+    fn maintain_book() {
+        let pair = KAssetPair(KAsset::XBT, KAsset::USD);
+        let mut book = OrderBook::from_snapshot(pair, 3, &snapshot());
+
+        assert_eq!(book.best_ask().unwrap().price, "5541.30000");
+        assert_eq!(book.best_bid().unwrap().price, "5541.20000");
+        assert!((book.spread().unwrap() - 0.1).abs() < 1e-9);
+        assert!((book.mid().unwrap() - 5541.25).abs() < 1e-9);
+        assert!((book.cumulative_volume(BookSide::Ask, "5541.8") - 2.837).abs() < 1e-9);
+        assert!((book.cumulative_volume(BookSide::Bid, "5539.9") - 1.829).abs() < 1e-9);
+
+        // Remove the best ask, insert a better bid and push the worst bid out of the depth
+        let mut update = KOWSBook {
+            snapshot: false,
+            asks: vec![level("5541.30000", "0.00000000")],
+            bids: vec![level("5541.25000", "1.00000000")],
+            checksum: None,
+        };
+        let expected = {
+            let mut hasher = crc32fast::Hasher::new();
+            for input in [
+                "55418000033000000",
+                "55427000064700000",
+                "554125000100000000",
+                "554120000152900000",
+                "55399000030000000",
+            ]
+            .iter()
+            {
+                hasher.update(input.as_bytes());
+            }
+            hasher.finalize()
+        };
+        update.checksum = Some(expected.to_string());
+        book.apply(&update).unwrap();
+
+        assert_eq!(book.best_ask().unwrap().price, "5541.80000");
+        assert_eq!(book.best_bid().unwrap().price, "5541.25000");
+        assert_eq!(book.bids().count(), 3);
+        assert!(book.depth_at_price(BookSide::Bid, "5539.5").is_none());
+        assert_eq!(
+            book.depth_at_price(BookSide::Bid, "5539.90000")
+                .unwrap()
+                .volume,
+            "0.30000000"
+        );
+
+        // A bad checksum marks the book as desynced until a new snapshot arrives
+        update.checksum = Some(String::from("1"));
+        assert!(matches!(
+            book.apply(&update).unwrap_err().0[..],
+            [KError::ChecksumMismatch]
+        ));
+        assert!(!book.is_synced());
+
+        let resnapshot = KOWSBook {
+            snapshot: true,
+            asks: vec![level("5600.00000", "1.00000000")],
+            bids: vec![level("5500.00000", "1.00000000")],
+            checksum: None,
+        };
+        book.apply(&resnapshot).unwrap();
+        assert!(book.is_synced());
+        assert_eq!(book.best_ask().unwrap().price, "5600.00000");
+    }
+
+    #[tokio::test]
+    async fn resubscribe_on_checksum_mismatch() {
+        use futures_util::stream::StreamExt;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for event in ["unsubscribe", "subscribe"].iter() {
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+                assert_eq!(
+                    request,
+                    serde_json::json!({
+                        "event": event,
+                        "pair": ["XBT/USD"],
+                        "subscription": { "name": "book", "depth": 10 },
+                    })
+                );
+            }
+        });
+
+        let mut client = KWSPublic::connect_with_url(&url).await.unwrap();
+        let pair = KAssetPair(KAsset::XBT, KAsset::USD);
+        let mut book = OrderBook::from_snapshot(pair, 10, &snapshot());
+
+        let update = KOWSBook {
+            snapshot: false,
+            asks: vec![level("5541.30000", "1.00000000")],
+            bids: Vec::new(),
+            checksum: Some(String::from("1")),
+        };
+        book.apply_or_resubscribe(&update, &mut client)
+            .await
+            .unwrap();
+        assert!(!book.is_synced());
+        server.await.unwrap();
+
+        // Updates sent before the new snapshot are dropped instead of resubscribing again
+        book.apply_or_resubscribe(&update, &mut client)
+            .await
+            .unwrap();
+        let resnapshot = KOWSBook {
+            snapshot: true,
+            asks: vec![level("5600.00000", "1.00000000")],
+            bids: vec![level("5500.00000", "1.00000000")],
+            checksum: None,
+        };
+        book.apply_or_resubscribe(&resnapshot, &mut client)
+            .await
+            .unwrap();
+        assert!(book.is_synced());
+        assert_eq!(book.best_ask().unwrap().price, "5600.00000");
+    }
+}
@@ -1,24 +1,40 @@
 //! Asynchronous HTTP client implementation sending instances of [KrakenInput] to the Kraken servers
-use hyper::body;
-use hyper::client::HttpConnector;
-use hyper::header::{CONTENT_TYPE, USER_AGENT};
-use hyper::{Body, Client, Request};
-use hyper_tls::HttpsConnector;
+use http::header::{CONTENT_TYPE, USER_AGENT};
+use http::Request;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::Future;
+use std::path::Path;
+use std::time::SystemTime;
 
 use super::auth::KrakenAuth;
 use super::error;
 use crate::api;
-use crate::api::{KResult, KrakenInput, KrakenResult, MethodType, Output};
-
-type HttpClient = Box<hyper::Client<HttpsConnector<HttpConnector>, hyper::Body>>;
+use crate::api::private::closed_orders::{KIClosedOrders, KOClosedOrders};
+use crate::api::private::open_orders::{KIOpenOrders, KOOpenOrders};
+use crate::api::Input;
+use crate::api::{BinaryOutput, KResult, KrakenInput, KrakenResult, MethodType, Output};
+use crate::limiter::RateLimiter;
+use crate::nonce::{MonotonicNonce, NonceSource};
+use crate::record::RecordingTransport;
+use crate::retry::{PlacedOrder, Retry, RetryPolicy};
+use crate::timeout::Timeouts;
+use crate::transport::{HyperTransport, MockTransport, Transport};
 
 /// Asynchronous HTTP client implementation sending instances of [KrakenInput] to the Kraken servers
 pub struct KrakenClient {
     url: String,
     version: String,
     auth: KrakenAuth,
-    client: HttpClient,
+    accounts: HashMap<String, KrakenAuth>,
+    limiter: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    timeouts: Timeouts,
+    nonce: Box<dyn NonceSource>,
+    transport: Box<dyn Transport>,
 }
 
 impl KrakenClient {
@@ -34,17 +50,26 @@ impl KrakenClient {
     /// If needing to call both public and private endpoints, a single authenticated client will
     /// suffice but unique clients can be used as well
     pub fn new(key: &str, secret: &str) -> Self {
-        let https = HttpsConnector::new();
+        KrakenClient::with_transport(key, secret, HyperTransport::new())
+    }
+
+    /// Construct a new KrakenClient instance sending its requests through `transport` instead of
+    /// the default [HyperTransport], i.e. a [MockTransport][crate::transport::MockTransport]
+    /// serving canned responses
+    pub fn with_transport<T>(key: &str, secret: &str, transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
         KrakenClient {
             url: String::from("https://api.kraken.com"),
             version: String::from("0"),
-            auth: KrakenAuth::new(&key, &secret),
-            client: Box::new(
-                Client::builder()
-                    .pool_idle_timeout(None)
-                    .http1_title_case_headers(true)
-                    .build::<_, hyper::Body>(https),
-            ),
+            auth: KrakenAuth::new(key, secret),
+            accounts: HashMap::new(),
+            limiter: None,
+            retry: None,
+            timeouts: Timeouts::default(),
+            nonce: Box::new(MonotonicNonce::new()),
+            transport: Box::new(transport),
         }
     }
 
@@ -65,7 +90,82 @@ impl KrakenClient {
 
     /// Assign new credentials for this KrakenClient
     pub fn set_auth(&mut self, key: &str, secret: &str) {
-        self.auth = KrakenAuth::new(&key, &secret);
+        self.auth = KrakenAuth::new(key, secret);
+    }
+
+    /// Register an additional set of credentials under `name`, i.e. for a subaccount. Requests can
+    /// be sent with these credentials using [request_as()][KrakenClient::request_as]. Registering
+    /// a name twice replaces the previous credentials
+    pub fn add_account(&mut self, name: &str, key: &str, secret: &str) {
+        self.accounts
+            .insert(name.to_string(), KrakenAuth::new(key, secret));
+    }
+
+    /// Remove the credentials registered under `name`. Returns whether any were registered
+    pub fn remove_account(&mut self, name: &str) -> bool {
+        self.accounts.remove(name).is_some()
+    }
+
+    /// Returns the names of every additional set of credentials registered on this client
+    pub fn accounts(&self) -> impl Iterator<Item = &String> {
+        self.accounts.keys()
+    }
+
+    /// Set a [RateLimiter] delaying private requests so they stay within Kraken's call and order
+    /// rate limits instead of failing. Pass `None` to disable rate limiting, which is the default
+    pub fn set_rate_limiter(&mut self, limiter: Option<RateLimiter>) {
+        self.limiter = limiter;
+    }
+
+    /// Returns the rate limiter used by this client, if any
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.limiter.as_ref()
+    }
+
+    /// Set a [RetryPolicy] resending requests that fail with transient errors. Pass `None` to
+    /// disable retries, which is the default. See the [retry module][crate::retry] for which
+    /// requests are retried
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry = policy;
+    }
+
+    /// Returns the retry policy used by this client, if any
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Set the [Timeouts] applied to every request. No timeouts are set by default. See the
+    /// [timeout module][crate::timeout] for how timed out orders are handled
+    pub fn set_timeouts(&mut self, timeouts: Timeouts) {
+        self.timeouts = timeouts;
+    }
+
+    /// Returns the timeouts applied to every request
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    /// Set the [NonceSource] signing private requests. Defaults to a [MonotonicNonce]. See the
+    /// [nonce module][crate::nonce] for sharing nonces between clients and processes
+    pub fn set_nonce_source<N>(&mut self, source: N)
+    where
+        N: NonceSource + 'static,
+    {
+        self.nonce = Box::new(source);
+    }
+
+    /// Record every request sent from now on, with its raw response body, to a new file at `path`
+    /// replacing any existing file. The recording can be replayed with a
+    /// [ReplayTransport][crate::record::ReplayTransport]. See the [record module][crate::record]
+    /// for what is recorded
+    pub fn record_to<P>(&mut self, path: P) -> KrakenResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::create(path)?;
+        let inner = std::mem::replace(&mut self.transport, Box::new(MockTransport::new()));
+        self.transport = Box::new(RecordingTransport::with_file(inner, file));
+        Ok(())
     }
 
     /// Returns the current base url that this client will send requests to
@@ -82,6 +182,12 @@ impl KrakenClient {
         &self.auth
     }
 
+    fn account(&self, name: &str) -> KrakenResult<&KrakenAuth> {
+        self.accounts
+            .get(name)
+            .ok_or_else(|| error::KrakenErrors(vec![error::KError::UnknownAccount]))
+    }
+
     /// Make a request to the desired API endpoint by passing a fully constructed [KrakenInput]
     ///
     /// ## Note
@@ -89,16 +195,247 @@ impl KrakenClient {
     /// The types of the input and the output must match otherwise the parsing will fail
     ///
     /// For instance: if `input` is constructed from a KITicker instance, then `T` must be KOTicker
-    pub async fn request<'a, T>(&self, input: &KrakenInput) -> KrakenResult<T>
+    pub async fn request<T>(&self, input: &KrakenInput) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        self.execute(input, self.auth(), &self.timeouts).await
+    }
+
+    /// Same as [request()][KrakenClient::request] but with `timeouts` overriding the client's
+    /// [timeouts][KrakenClient::set_timeouts] for this call. Timeouts not set in `timeouts` fall
+    /// back to the client's
+    pub async fn request_with_timeouts<T>(
+        &self,
+        input: &KrakenInput,
+        timeouts: &Timeouts,
+    ) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        self.execute(input, self.auth(), &timeouts.or(&self.timeouts))
+            .await
+    }
+
+    /// Make a request with the credentials registered under `account` with
+    /// [add_account()][KrakenClient::add_account] instead of the client's own credentials.
+    /// Returns [KError::UnknownAccount][error::KError::UnknownAccount] if no credentials are
+    /// registered under that name
+    pub async fn request_as<T>(&self, account: &str, input: &KrakenInput) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        self.execute(input, self.account(account)?, &self.timeouts)
+            .await
+    }
+
+    /// Make a request to an API endpoint that responds with a file instead of JSON, i.e. the
+    /// [Retrieve Data Export][crate::private::retrieve_export::KIRetrieveExport] endpoint
+    ///
+    /// ## Note
+    ///
+    /// Kraken still responds with JSON when the request fails, in which case the errors are
+    /// parsed and returned as usual
+    pub async fn request_binary<T>(&self, input: &KrakenInput) -> KrakenResult<T>
+    where
+        T: BinaryOutput,
+    {
+        let timeouts = &self.timeouts;
+        let bytes =
+            KrakenClient::with_total(timeouts, self.send(input, self.auth(), timeouts)).await?;
+        if let Ok(parsed) = serde_json::from_slice::<KResult<Value>>(&bytes) {
+            if !parsed.error.is_empty() {
+                return Err(error::generate_errors(parsed.error));
+            }
+        }
+        Ok(T::from_bytes(bytes))
+    }
+
+    // Send the request within the total timeout, looking up orders whose request timed out
+    async fn execute<T>(
+        &self,
+        input: &KrakenInput,
+        auth: &KrakenAuth,
+        timeouts: &Timeouts,
+    ) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        let submitted = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let result =
+            KrakenClient::with_total(timeouts, self.attempt(input, auth, timeouts, submitted))
+                .await;
+
+        match (result, Retry::of(input)) {
+            (Err(errors), Retry::Dedupe(order)) if KrakenClient::timed_out(&errors) => {
+                // The total timeout may have run out already, so the lookup gets one of its own
+                let lookup = self.find_order(&order, submitted, auth, timeouts);
+                match KrakenClient::with_total(timeouts, lookup).await {
+                    // The order was placed even though its request timed out
+                    Ok(Some(order)) => Ok(serde_json::from_value(order)?),
+                    _ => Err(errors),
+                }
+            }
+            (result, _) => result,
+        }
+    }
+
+    // Send the request, retrying it according to the retry policy if one is set
+    async fn attempt<T>(
+        &self,
+        input: &KrakenInput,
+        auth: &KrakenAuth,
+        timeouts: &Timeouts,
+        submitted: u64,
+    ) -> KrakenResult<T>
     where
         T: Output + DeserializeOwned,
     {
+        let policy = match &self.retry {
+            Some(policy) => policy,
+            None => return KrakenClient::parse(&self.send(input, auth, timeouts).await?),
+        };
+
+        let retry = Retry::of(input);
+        let mut attempt = 1;
+        loop {
+            let errors = match self.send(input, auth, timeouts).await {
+                Ok(bytes) => match KrakenClient::parse(&bytes) {
+                    Ok(output) => return Ok(output),
+                    Err(errors) => errors,
+                },
+                Err(errors) => errors,
+            };
+
+            if attempt >= policy.max_attempts() || !policy.is_retryable(&errors) {
+                return Err(errors);
+            }
+            if let Retry::Never = retry {
+                return Err(errors);
+            }
+
+            tokio::time::sleep(policy.backoff(attempt)).await;
+            if let Retry::Dedupe(order) = &retry {
+                match self.find_order(order, submitted, auth, timeouts).await {
+                    // The failed attempt placed the order after all
+                    Ok(Some(order)) => return Ok(serde_json::from_value(order)?),
+                    Ok(None) => {}
+                    // Without knowing whether the order was placed it can't be resent safely
+                    Err(_) => return Err(errors),
+                }
+            }
+
+            attempt += 1;
+        }
+    }
+
+    // Look for an order placed by `placed` that was opened at or after `since`, returning it in
+    // the layout of the AddOrder response
+    async fn find_order(
+        &self,
+        placed: &PlacedOrder,
+        since: u64,
+        auth: &KrakenAuth,
+        timeouts: &Timeouts,
+    ) -> KrakenResult<Option<Value>> {
+        let userref = placed.userref();
+        let open = KIOpenOrders::build().with_userref(userref).finish();
+        let open: KOOpenOrders = KrakenClient::parse(&self.send(&open, auth, timeouts).await?)?;
+        let closed = KIClosedOrders::build()
+            .with_userref(userref)
+            .starting_timestamp(since.saturating_sub(1))
+            .finish();
+        let closed: KOClosedOrders =
+            KrakenClient::parse(&self.send(&closed, auth, timeouts).await?)?;
+
+        let found = open
+            .orders
+            .into_iter()
+            .chain(closed.closed)
+            .find(|(_, order)| {
+                placed.matches(order) && api::timestamp_secs(&order.opentm) + 1.0 >= since as f64
+            });
+        Ok(found.map(|(txid, order)| {
+            serde_json::json!({
+                "descr": { "order": order.descr.desc },
+                "txid": [txid],
+            })
+        }))
+    }
+
+    fn parse<T>(bytes: &[u8]) -> KrakenResult<T>
+    where
+        T: Output + DeserializeOwned,
+    {
+        let parsed: KResult<T> = serde_json::from_slice(bytes)?;
+
+        let api_errors = parsed.error;
+        match api_errors.len() {
+            0 => Ok(parsed.result.unwrap()),
+            _ => Err(error::generate_errors(api_errors)),
+        }
+    }
+
+    // Send the request signed with `auth` and a fresh nonce within the request timeout and return
+    // the raw response body
+    async fn send(
+        &self,
+        input: &KrakenInput,
+        auth: &KrakenAuth,
+        timeouts: &Timeouts,
+    ) -> KrakenResult<Vec<u8>> {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire(auth.key(), input).await;
+        }
+        let input = match input.info().method() {
+            MethodType::Private => {
+                let mut input = input.clone();
+                input.set_nonce(self.nonce.next().await?);
+                Cow::Owned(input)
+            }
+            MethodType::Public => Cow::Borrowed(input),
+        };
+        let mut request = self.build_request(&input, auth);
+        // Lets the transport apply the connect timeout
+        request.extensions_mut().insert(*timeouts);
+        match timeouts.request() {
+            Some(timeout) => tokio::time::timeout(timeout, self.transport.send(request))
+                .await
+                .unwrap_or_else(|_| Err(error::KrakenErrors(vec![error::KError::Timeout]))),
+            None => self.transport.send(request).await,
+        }
+    }
+
+    // Run `future` within the total timeout
+    async fn with_total<F, T>(timeouts: &Timeouts, future: F) -> KrakenResult<T>
+    where
+        F: Future<Output = KrakenResult<T>>,
+    {
+        match timeouts.total() {
+            Some(timeout) => tokio::time::timeout(timeout, future)
+                .await
+                .unwrap_or_else(|_| Err(error::KrakenErrors(vec![error::KError::Timeout]))),
+            None => future.await,
+        }
+    }
+
+    fn timed_out(errors: &error::KrakenErrors<error::KError>) -> bool {
+        errors
+            .0
+            .iter()
+            .any(|err| matches!(err, error::KError::Timeout))
+    }
+
+    fn build_request(&self, input: &KrakenInput, auth: &KrakenAuth) -> Request<Vec<u8>> {
         match input.info().method() {
             MethodType::Public => {
                 let endpoint = format!(
                     "/{}/{}/{}",
                     self.version(),
-                    input.info().method().to_string(),
+                    input.info().method(),
                     input.info().endpoint()
                 );
                 let formatted_params = api::format_params(&input.params());
@@ -110,7 +447,7 @@ impl KrakenClient {
                 let mut request = Request::builder()
                     .method("GET")
                     .uri(full_url)
-                    .body(Body::empty())
+                    .body(Vec::new())
                     .expect("Failed to form a correct http request");
 
                 request.headers_mut().insert(
@@ -122,31 +459,29 @@ impl KrakenClient {
                     "application/x-www-form-urlencoded".parse().unwrap(),
                 );
 
-                let parsed: KResult<T> = serde_json::from_slice(
-                    &body::to_bytes(self.client.request(request).await?).await?,
-                )?;
-
-                let api_errors = parsed.error;
-                match api_errors.len() {
-                    0 => Ok(parsed.result.unwrap()),
-                    _ => Err(error::generate_errors(api_errors)),
-                }
+                request
             }
 
             MethodType::Private => {
                 let endpoint = format!(
                     "/{}/{}/{}",
                     self.version(),
-                    input.info().method().to_string(),
+                    input.info().method(),
                     input.info().endpoint()
                 );
                 let params = input.params();
-                let formatted_params = api::format_params(&params).unwrap();
+                let json_body = input.json_body();
+                let content_type = match json_body {
+                    Some(_) => "application/json",
+                    None => "application/x-www-form-urlencoded",
+                };
+                let formatted_params =
+                    json_body.unwrap_or_else(|| api::format_params(&params).unwrap());
                 // FIXME: Clean up the details behind get_params(), format_params() and KrakenInput
                 // It seems to work but the references are fragile
-                let signature = self.auth().sign(
+                let signature = auth.sign(
                     &endpoint,
-                    &params
+                    params
                         .expect("Add nonce when building private methods")
                         .get("nonce")
                         .expect("Add nonce when building private methods"),
@@ -157,33 +492,24 @@ impl KrakenClient {
                 let mut request = Request::builder()
                     .method("POST")
                     .uri(full_url)
-                    .body(Body::from(formatted_params))
+                    .body(formatted_params.into_bytes())
                     .expect("Failed to form a correct http request");
 
                 request.headers_mut().insert(
                     USER_AGENT,
                     "krakenapi/0.1 (Kraken Rust Client)".parse().unwrap(),
                 );
-                request.headers_mut().insert(
-                    CONTENT_TYPE,
-                    "application/x-www-form-urlencoded".parse().unwrap(),
-                );
                 request
                     .headers_mut()
-                    .insert("API-Key", self.auth().key().parse().unwrap());
+                    .insert(CONTENT_TYPE, content_type.parse().unwrap());
+                request
+                    .headers_mut()
+                    .insert("API-Key", auth.key().parse().unwrap());
                 request
                     .headers_mut()
                     .insert("API-Sign", signature.parse().unwrap());
 
-                let parsed: KResult<T> = serde_json::from_slice(
-                    &body::to_bytes(self.client.request(request).await?).await?,
-                )?;
-
-                let api_errors = parsed.error;
-                match api_errors.len() {
-                    0 => Ok(parsed.result.unwrap()),
-                    _ => Err(error::generate_errors(api_errors)),
-                }
+                request
             }
         }
     }
@@ -220,4 +546,163 @@ mod tests {
             (String::from("newkey"), String::from("newsecret"))
         );
     }
+
+    #[test]
+    fn manage_accounts() {
+        let mut client = KrakenClient::new("key", "secret");
+        client.add_account("desk1", "subkey", "subsecret");
+
+        assert_eq!(client.accounts().collect::<Vec<_>>(), vec!["desk1"]);
+        assert_eq!(client.account("desk1").unwrap().key(), "subkey");
+        assert!(client.account("desk2").is_err());
+
+        assert!(client.remove_account("desk1"));
+        assert!(!client.remove_account("desk1"));
+    }
+
+    #[tokio::test]
+    async fn request_with_mock_transport() {
+        use crate::api::private::account_balance::{KIAccountBalance, KOAccountBalance};
+        use crate::api::public::server_time::{KIServerTime, KOServerTime};
+        use crate::error::KError;
+        use crate::transport::MockTransport;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let mock = Arc::new(
+            MockTransport::new()
+                .with_result(
+                    "Time",
+                    serde_json::json!({ "unixtime": 1616336594, "rfc1123": "Sun, 21 Mar 21 14:23:14 +0000" }),
+                )
+                .with_errors("Balance", &["EService:Busy"])
+                .with_errors("Balance", &["EService:Busy"])
+                .with_result("Balance", serde_json::json!({ "ZUSD": "171288.6158" })),
+        );
+        let mut client = KrakenClient::with_transport("key", "c2VjcmV0", mock.clone());
+
+        let time = client
+            .request::<KOServerTime>(&KIServerTime::build())
+            .await
+            .unwrap();
+        assert_eq!(time.unixtime, 1616336594);
+
+        let err = client
+            .request::<KOAccountBalance>(&KIAccountBalance::build())
+            .await
+            .unwrap_err();
+        assert!(matches!(err.0[..], [KError::ServiceBusy]));
+
+        // Retried requests are signed with a fresh nonce
+        client.set_retry_policy(Some(
+            RetryPolicy::new().with_initial_backoff(Duration::from_millis(1)),
+        ));
+        let balance = client
+            .request::<KOAccountBalance>(&KIAccountBalance::build())
+            .await
+            .unwrap();
+        assert_eq!(balance.balances.len(), 1);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].endpoint, "Time");
+        assert!(requests[1].body.starts_with("nonce="));
+        assert_ne!(requests[2].body, requests[3].body);
+    }
+
+    #[tokio::test]
+    async fn timed_out_order_lookup() {
+        use crate::api::asset::{KAsset, KAssetPair};
+        use crate::api::private::add_order::{KIAddOrder, KOAddOrder};
+        use crate::api::{OrderType, TradeType};
+        use crate::error::KError;
+        use crate::transport::{self, MockTransport};
+        use futures_util::future::{self, BoxFuture};
+        use std::time::Duration;
+
+        // Never answers any request
+        struct Unresponsive;
+        impl Transport for Unresponsive {
+            fn send(&self, _: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+                Box::pin(future::pending())
+            }
+        }
+
+        // Never answers AddOrder requests
+        struct Stalled(MockTransport);
+        impl Transport for Stalled {
+            fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+                match transport::endpoint(&request).as_str() {
+                    "AddOrder" => Box::pin(future::pending()),
+                    _ => self.0.send(request),
+                }
+            }
+        }
+
+        let opentm = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let open_order = |price: &str| {
+            serde_json::json!({ "open": { "OQCLML-BW3P3-BUCMWZ": {
+                "refid": null, "userref": 42, "status": "open", "opentm": opentm,
+                "starttm": 0, "expiretm": 0, "vol": "1.00000000", "vol_exec": "0.0", "cost": "0",
+                "fee": "0", "price": "0", "misc": "", "oflags": "fciq",
+                "descr": {
+                    "pair": "XBTUSD", "type": "buy", "ordertype": "limit",
+                    "price": price, "price2": "0", "leverage": "none",
+                    "order": format!("buy 1.00000000 XBTUSD @ limit {}", price), "close": ""
+                }
+            }}})
+        };
+        let mock = MockTransport::new()
+            .with_result(
+                "ClosedOrders",
+                serde_json::json!({ "closed": {}, "count": 0 }),
+            )
+            // Another order with the same userref
+            .with_result("OpenOrders", open_order("29000.0"))
+            .with_result("OpenOrders", open_order("30010.0"));
+        let mut client = KrakenClient::with_transport("key", "c2VjcmV0", Stalled(mock));
+        client.set_timeouts(Timeouts::new().with_total(Duration::from_secs(5)));
+
+        let order = || {
+            KIAddOrder::build(
+                KAssetPair(KAsset::XBT, KAsset::USD),
+                TradeType::Buy,
+                OrderType::Limit(String::from("30010.0")),
+                1.0,
+            )
+            .with_userref(42)
+            .finish()
+        };
+        let timeouts = Timeouts::new().with_request(Duration::from_millis(10));
+
+        // No order matching the request was placed
+        let err = client
+            .request_with_timeouts::<KOAddOrder>(&order(), &timeouts)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.0[..], [KError::Timeout]));
+
+        // The order was placed although its request timed out
+        let placed = client
+            .request_with_timeouts::<KOAddOrder>(&order(), &timeouts)
+            .await
+            .unwrap();
+        assert_eq!(placed.txid, Some(vec![String::from("OQCLML-BW3P3-BUCMWZ")]));
+
+        // Kraken stops answering altogether and only the total timeout is set, which bounds the
+        // lookup as well
+        let client = KrakenClient::with_transport("key", "c2VjcmV0", Unresponsive);
+        let timeouts = Timeouts::new().with_total(Duration::from_millis(50));
+        let start = std::time::Instant::now();
+        let err = client
+            .request_with_timeouts::<KOAddOrder>(&order(), &timeouts)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.0[..], [KError::Timeout]));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
 }
@@ -2,9 +2,15 @@
 
 use std::error::Error;
 use std::fmt;
+use std::io::Error as IoError;
 
+use csv::Error as CsvError;
 use hyper::Error as HyperError;
 use serde_json::Error as SerdeError;
+use tokio_tungstenite::tungstenite::Error as WebSocketError;
+use zip::result::ZipError;
+
+use crate::api::private::add_order::OrderViolation;
 
 /// Newtype wrapper around a vector of error values
 #[derive(Debug)]
@@ -34,9 +40,47 @@ pub enum KError {
     /// structure
     ParseError(SerdeError),
 
+    /// Wrapper around [tungstenite::Error][WebSocketError] when an internal websocket error has
+    /// occurred
+    WebSocketError(WebSocketError),
+
+    /// Wrapper around [zip::result::ZipError][ZipError] when a data export archive could not be
+    /// read
+    ZipError(ZipError),
+
+    /// Wrapper around [csv::Error][CsvError] when a data export report could not be parsed
+    CsvError(CsvError),
+
+    /// Wrapper around [std::io::Error][IoError] when a recording of API traffic could not be
+    /// written or read
+    IoError(IoError),
+
     /// Failed to parse into KAsset/KAssetPair
     AssetParseError,
 
+    /// An order batch is empty, exceeds the maximum batch size, or contains orders for more than
+    /// one asset pair
+    InvalidBatch,
+
+    /// An order failed local validation against its asset pair's info or the system status and
+    /// was not sent | See [KIAddOrder::check()][crate::private::add_order::KIAddOrder::check]
+    InvalidOrder(OrderViolation),
+
+    /// No credentials are registered on the client under the requested account name
+    UnknownAccount,
+
+    /// The CRC32 checksum sent by Kraken does not match the locally maintained order book. The
+    /// book is out of sync and needs a fresh snapshot
+    ChecksumMismatch,
+
+    /// A replayed request doesn't match the next recorded request, or every recorded request has
+    /// already been replayed
+    ReplayMismatch,
+
+    /// A request didn't complete within its connect, request or total
+    /// [timeout][crate::timeout::Timeouts]. The request may still have been processed by Kraken
+    Timeout,
+
     /// Invalid currency pair
     /// You can pull the complete list of our asset pairs from the AssetPairs public call
     /// and look for the pair name as the entry of the Json headers or by the parameter
@@ -191,11 +235,21 @@ impl fmt::Display for KError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             // Errors from internal dependencies
-            KError::HttpError(err) => write!(f, "HTTP Error: {}", err.to_string()),
-            KError::ParseError(err) => write!(f, "Parse Error: {}", err.to_string()),
+            KError::HttpError(err) => write!(f, "HTTP Error: {}", err),
+            KError::ParseError(err) => write!(f, "Parse Error: {}", err),
+            KError::WebSocketError(err) => write!(f, "WebSocket Error: {}", err),
+            KError::ZipError(err) => write!(f, "Zip Error: {}", err),
+            KError::CsvError(err) => write!(f, "CSV Error: {}", err),
+            KError::IoError(err) => write!(f, "IO Error: {}", err),
 
             // Errors from processing within this crate
             KError::AssetParseError => write!(f, "Failed to parse string into KAsset"),
+            KError::InvalidBatch => write!(f, "Invalid Order Batch"),
+            KError::InvalidOrder(violation) => write!(f, "Invalid Order: {}", violation),
+            KError::UnknownAccount => write!(f, "Unknown Account"),
+            KError::ChecksumMismatch => write!(f, "Order Book Checksum Mismatch"),
+            KError::ReplayMismatch => write!(f, "Replay Mismatch"),
+            KError::Timeout => write!(f, "Request Timed Out"),
 
             // Errors coming directly from Kraken's servers
             KError::UnknownAssetPair => write!(f, "Unknown AssetPair"),
@@ -238,12 +292,38 @@ impl From<SerdeError> for KrakenErrors<KError> {
     }
 }
 
+impl From<WebSocketError> for KrakenErrors<KError> {
+    fn from(err: WebSocketError) -> Self {
+        KrakenErrors(vec![KError::WebSocketError(err)])
+    }
+}
+
+impl From<ZipError> for KrakenErrors<KError> {
+    fn from(err: ZipError) -> Self {
+        KrakenErrors(vec![KError::ZipError(err)])
+    }
+}
+
+impl From<CsvError> for KrakenErrors<KError> {
+    fn from(err: CsvError) -> Self {
+        KrakenErrors(vec![KError::CsvError(err)])
+    }
+}
+
+impl From<IoError> for KrakenErrors<KError> {
+    fn from(err: IoError) -> Self {
+        KrakenErrors(vec![KError::IoError(err)])
+    }
+}
+
 pub(crate) fn generate_errors(errors: Vec<String>) -> KrakenErrors<KError> {
     let mut errs: Vec<KError> = Vec::with_capacity(errors.len());
     for error in errors {
-        let index = error.find(':').unwrap();
         // Assume kraken will not return an error like "EError:" with no error description
-        let (category, message) = error.split_at(index + 1);
+        let (category, message) = match error.find(':') {
+            Some(index) => error.split_at(index + 1),
+            None => ("", error.as_str()),
+        };
 
         let err = match message {
             "Unknown asset pair" => KError::UnknownAssetPair,
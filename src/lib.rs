@@ -8,6 +8,17 @@
 //! - Type-driven and self-validating API
 //! - Ergonomic and easy to use
 //! - Promotes re-use of structures and avoids unecessary allocations or redundant HTTP clients
+//! - Websocket streaming of public market data and authenticated trade/order feeds
+//! - Local order book maintenance with checksum validation
+//! - Optional client-side rate limiting modelled on Kraken's call counters
+//! - Strictly increasing [nonces][nonce], optionally persisted or shared between processes
+//! - [Streams][paginate] paging through the full order, trade and ledger history
+//! - [Polling streams][poll] of new candles, trades and spreads
+//! - Pluggable HTTP transport with recording and replay of API traffic
+//! - Runtime [registry] of listed assets and pairs, so new listings parse without a release
+//! - Optional [blocking client][blocking] behind the `blocking` feature
+//! - Optional exact [decimal][api::KDecimal] prices and amounts behind the `decimal` feature
+//! - Optional typed [timestamps][api::KTimestamp] behind the `timestamps` feature
 //!
 //! # General Notes - TLDR
 //!
@@ -122,8 +133,21 @@
 
 pub mod api;
 mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod book;
 pub mod client;
 pub mod error;
+pub mod limiter;
+pub mod nonce;
+pub mod paginate;
+pub mod poll;
+pub mod record;
+pub mod registry;
+pub mod retry;
+pub mod timeout;
+pub mod transport;
+pub mod ws;
 
 pub use api::private;
 pub use api::public;
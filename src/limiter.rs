@@ -0,0 +1,268 @@
+//! Client-side rate limiter modelled on Kraken's API and order rate counters
+//!
+//! # Note
+//! Kraken keeps a call counter per account which is increased by every private request and
+//! decays over time at a rate depending on the account's verification tier. Requests made while
+//! the counter is above the tier's maximum fail with
+//! [KError::APIRateLimit][crate::error::KError::APIRateLimit], and repeated violations lead to a
+//! temporary lockout. Order placement has its own counter per asset pair which fails with
+//! [KError::OrderRateLimit][crate::error::KError::OrderRateLimit].
+//!
+//! A [RateLimiter] set on a [KrakenClient][crate::client::KrakenClient] mirrors both counters
+//! locally and delays requests until they can be sent without exceeding either limit. Public
+//! endpoints are not counted.
+//!
+//! Cancelling orders increases Kraken's order rate counter by an amount depending on how long the
+//! order was resting on the book. Cancel requests only carry a transaction ID so this penalty is
+//! not modelled and some headroom should be left when cancelling frequently
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::api::{KrakenInput, MethodType};
+
+/// Account verification tier, which determines the maximum and decay rate of Kraken's counters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerificationTier {
+    Starter,
+    Intermediate,
+    Pro,
+}
+
+impl VerificationTier {
+    // (maximum, decay per second) of the API counter
+    fn api_limits(&self) -> (f64, f64) {
+        match self {
+            VerificationTier::Starter => (15.0, 0.33),
+            VerificationTier::Intermediate => (20.0, 0.5),
+            VerificationTier::Pro => (20.0, 1.0),
+        }
+    }
+
+    // (maximum, decay per second) of the order rate counter
+    fn order_limits(&self) -> (f64, f64) {
+        match self {
+            VerificationTier::Starter => (60.0, 1.0),
+            VerificationTier::Intermediate => (125.0, 2.34),
+            VerificationTier::Pro => (180.0, 3.75),
+        }
+    }
+}
+
+// Counter that decays linearly over time
+#[derive(Debug, Clone, Copy)]
+struct Counter {
+    value: f64,
+    updated: Instant,
+}
+
+impl Counter {
+    fn new(now: Instant) -> Self {
+        Counter {
+            value: 0.0,
+            updated: now,
+        }
+    }
+
+    fn decay(&mut self, now: Instant, rate: f64) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.value = (self.value - elapsed * rate).max(0.0);
+        self.updated = now;
+    }
+
+    // Time until `cost` can be added without going over `max`
+    fn wait(&self, cost: f64, max: f64, rate: f64) -> Duration {
+        let excess = self.value + cost - max;
+        if excess <= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(excess / rate)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    // API counters keyed by API key, so every set of credentials on a client is tracked
+    // separately
+    api: HashMap<String, Counter>,
+    // Order rate counters keyed by API key and asset pair
+    orders: HashMap<(String, String), Counter>,
+}
+
+/// Rate limiter delaying private requests so they stay within Kraken's call and order rate
+/// limits | See the [module documentation][self]
+#[derive(Debug)]
+pub struct RateLimiter {
+    tier: VerificationTier,
+    costs: HashMap<String, f64>,
+    counters: Mutex<Counters>,
+}
+
+impl RateLimiter {
+    /// Construct a rate limiter for an account of the given verification tier, with Kraken's
+    /// default endpoint costs. Ledger and trade history queries cost 2, order management doesn't
+    /// count against the API counter and every other private endpoint costs 1
+    pub fn new(tier: VerificationTier) -> Self {
+        let mut costs = HashMap::new();
+        for endpoint in ["Ledgers", "QueryLedgers", "TradesHistory"].iter() {
+            costs.insert(endpoint.to_string(), 2.0);
+        }
+        for endpoint in [
+            "AddOrder",
+            "AddOrderBatch",
+            "EditOrder",
+            "CancelOrder",
+            "CancelOrderBatch",
+            "CancelAll",
+            "CancelAllOrdersAfter",
+        ]
+        .iter()
+        {
+            costs.insert(endpoint.to_string(), 0.0);
+        }
+
+        RateLimiter {
+            tier,
+            costs,
+            counters: Mutex::new(Counters::default()),
+        }
+    }
+
+    /// Override the API counter cost of a private endpoint, i.e. "TradesHistory"
+    pub fn with_cost(mut self, endpoint: &str, cost: f64) -> Self {
+        self.costs.insert(endpoint.to_string(), cost);
+        self
+    }
+
+    /// Verification tier the limiter was constructed with
+    pub fn tier(&self) -> VerificationTier {
+        self.tier
+    }
+
+    /// API counter cost of a private endpoint
+    pub fn cost(&self, endpoint: &str) -> f64 {
+        self.costs.get(endpoint).copied().unwrap_or(1.0)
+    }
+
+    // Wait until `input` can be sent with the credentials identified by `key`, then count it
+    pub(crate) async fn acquire(&self, key: &str, input: &KrakenInput) {
+        loop {
+            let wait = self.try_acquire(key, input, Instant::now());
+            if wait == Duration::from_secs(0) {
+                return;
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Count `input` if it can be sent at `now`, otherwise return how long to wait before trying
+    // again
+    fn try_acquire(&self, key: &str, input: &KrakenInput, now: Instant) -> Duration {
+        if let MethodType::Public = input.info().method() {
+            return Duration::from_secs(0);
+        }
+
+        let (api_max, api_rate) = self.tier.api_limits();
+        let (order_max, order_rate) = self.tier.order_limits();
+        let cost = self.cost(input.info().endpoint());
+        let order = order_cost(input);
+
+        let mut counters = self.counters.lock().unwrap();
+        let mut api = *counters
+            .api
+            .entry(key.to_string())
+            .or_insert_with(|| Counter::new(now));
+        api.decay(now, api_rate);
+        let mut wait = api.wait(cost, api_max, api_rate);
+
+        let mut orders = None;
+        if let Some((pair, count)) = &order {
+            let mut counter = *counters
+                .orders
+                .entry((key.to_string(), pair.clone()))
+                .or_insert_with(|| Counter::new(now));
+            counter.decay(now, order_rate);
+            wait = wait.max(counter.wait(*count, order_max, order_rate));
+            orders = Some(counter);
+        }
+
+        if wait == Duration::from_secs(0) {
+            api.value += cost;
+            if let (Some((pair, count)), Some(mut counter)) = (order, orders) {
+                counter.value += count;
+                counters.orders.insert((key.to_string(), pair), counter);
+            }
+        }
+        counters.api.insert(key.to_string(), api);
+        wait
+    }
+}
+
+// Asset pair and number of orders placed by an order management request
+fn order_cost(input: &KrakenInput) -> Option<(String, f64)> {
+    let params = input.params()?;
+    let pair = params.get("pair")?.clone();
+    match input.info().endpoint().as_str() {
+        "AddOrder" | "EditOrder" => Some((pair, 1.0)),
+        "AddOrderBatch" => {
+            let count = params
+                .keys()
+                .filter(|key| key.starts_with("orders%5B") && key.ends_with("%5D%5Bordertype%5D"))
+                .count();
+            Some((pair, count as f64))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::{KAsset, KAssetPair};
+    use crate::api::private::add_order::KIAddOrder;
+    use crate::api::private::trade_history::KITradeHistory;
+    use crate::api::public::server_time::KIServerTime;
+    use crate::api::{Input, OrderType, TradeType};
+
+    #[test]
+    fn count_private_requests() {
+        let limiter = RateLimiter::new(VerificationTier::Starter);
+        let start = Instant::now();
+        let zero = Duration::from_secs(0);
+
+        // Public requests are never delayed
+        for _ in 0..100 {
+            assert_eq!(
+                limiter.try_acquire("key", &KIServerTime::build(), start),
+                zero
+            );
+        }
+
+        let history = KITradeHistory::build().finish();
+        for _ in 0..7 {
+            assert_eq!(limiter.try_acquire("key", &history, start), zero);
+        }
+        // 14 of 15 used, a cost of 2 goes one over and needs ~3 seconds of decay
+        let wait = limiter.try_acquire("key", &history, start);
+        assert!((wait.as_secs_f64() - 1.0 / 0.33).abs() < 0.01);
+        assert_eq!(
+            limiter.try_acquire("key", &history, start + Duration::from_secs(4)),
+            zero
+        );
+        // Other credentials have their own counter
+        assert_eq!(limiter.try_acquire("other", &history, start), zero);
+
+        let order = KIAddOrder::build(
+            KAssetPair(KAsset::XBT, KAsset::USD),
+            TradeType::Buy,
+            OrderType::Market,
+            1.0,
+        )
+        .finish();
+        for _ in 0..60 {
+            assert_eq!(limiter.try_acquire("key", &order, start), zero);
+        }
+        assert!(limiter.try_acquire("key", &order, start) > zero);
+    }
+}
@@ -0,0 +1,213 @@
+//! Nonces signing the private requests of a [KrakenClient][crate::client::KrakenClient]
+//!
+//! # Note
+//! Kraken rejects a private request with
+//! [KError::InvalidNonce][crate::error::KError::InvalidNonce] unless its nonce is greater than the
+//! nonce of the previous request made with the same API key, and temporarily locks out keys
+//! sending too many invalid nonces. The client takes the nonce of
+//! every private request, including each retry, from its [NonceSource] right before signing it,
+//! after waiting on the [rate limiter][crate::limiter]. The nonce set by the input builders is
+//! replaced.
+//!
+//! The default source is a [MonotonicNonce], which starts at the current time in microseconds
+//! and never repeats or goes backwards, even for requests made in the same microsecond, from
+//! parallel tasks, or across a backwards step of the system clock. Use
+//! [MonotonicNonce::persistent()] to also keep the last nonce in a file, so a restarted process
+//! continues from it. Clients sharing an API key must share a source, i.e. by passing clones of an
+//! [Arc] holding it to [set_nonce_source()][crate::client::KrakenClient::set_nonce_source].
+//! Processes sharing an API key need a [NonceSource] of their own backed by some shared state,
+//! i.e. a database sequence. Requests can still reach Kraken in a different order than their
+//! nonces were issued, which the
+//! [nonce window](https://support.kraken.com/hc/en-us/articles/360001148023) of the API key
+//! tolerates
+//!
+//! ```no_run
+//! use kraapi::client::KrakenClient;
+//! use kraapi::nonce::MonotonicNonce;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = KrakenClient::new("<API-Key>", "<API-Secret>");
+//! client.set_nonce_source(MonotonicNonce::persistent("kraken.nonce")?);
+//! # Ok(())
+//! # }
+//! ```
+use futures_util::future::BoxFuture;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::api::KrakenResult;
+
+/// Source of the nonces of private requests | See the [module documentation][self]
+pub trait NonceSource: Send + Sync {
+    /// Returns a nonce greater than every nonce returned before for the same API key
+    fn next(&self) -> BoxFuture<'_, KrakenResult<u64>>;
+}
+
+impl<T> NonceSource for Arc<T>
+where
+    T: NonceSource + ?Sized,
+{
+    fn next(&self) -> BoxFuture<'_, KrakenResult<u64>> {
+        (**self).next()
+    }
+}
+
+impl<T> NonceSource for Box<T>
+where
+    T: NonceSource + ?Sized,
+{
+    fn next(&self) -> BoxFuture<'_, KrakenResult<u64>> {
+        (**self).next()
+    }
+}
+
+/// Strictly increasing nonces based on the current time in microseconds, optionally persisted to
+/// a file | See the [module documentation][self]
+#[derive(Debug, Default)]
+pub struct MonotonicNonce {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    last: u64,
+    path: Option<PathBuf>,
+}
+
+impl MonotonicNonce {
+    /// Construct a source starting at the current time
+    pub fn new() -> Self {
+        MonotonicNonce::default()
+    }
+
+    /// Construct a source writing every nonce to the file at `path`, continuing from the nonce
+    /// stored in it if the file exists. Fails if the file doesn't hold a nonce
+    ///
+    /// Each nonce is written to a temporary file next to `path`, synced to disk and renamed over
+    /// `path` before it is handed out, so a crash never leaves a partially written nonce behind.
+    /// This costs a disk sync per private request, which
+    /// [next()][NonceSource::next] runs on tokio's blocking thread pool
+    pub fn persistent<P>(path: P) -> KrakenResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let last = match fs::read_to_string(path) {
+            Ok(last) => last.trim().parse().map_err(|_| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} doesn't hold a nonce", path.display()),
+                )
+            })?,
+            Err(err) if err.kind() == ErrorKind::NotFound => 0,
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(MonotonicNonce {
+            state: Arc::new(Mutex::new(State {
+                last,
+                path: Some(path.to_path_buf()),
+            })),
+        })
+    }
+
+    /// Returns the next nonce, the current time in microseconds or one more than the last nonce
+    /// if that is greater. Blocks on writing the nonce to disk if the source is persistent
+    pub fn next_nonce(&self) -> KrakenResult<u64> {
+        self.state.lock().unwrap().next()
+    }
+
+    /// Returns the last nonce handed out, or the one loaded from the file if none was yet
+    pub fn last(&self) -> u64 {
+        self.state.lock().unwrap().last
+    }
+}
+
+impl State {
+    fn next(&mut self) -> KrakenResult<u64> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        let now = now.as_secs() * 1_000_000 + u64::from(now.subsec_micros());
+
+        let nonce = now.max(self.last + 1);
+        if let Some(path) = &self.path {
+            let mut temp = path.clone().into_os_string();
+            temp.push(".tmp");
+            let mut file = File::create(&temp)?;
+            file.write_all(nonce.to_string().as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temp, path)?;
+        }
+        self.last = nonce;
+        Ok(nonce)
+    }
+}
+
+impl NonceSource for MonotonicNonce {
+    fn next(&self) -> BoxFuture<'_, KrakenResult<u64>> {
+        let persistent = self.state.lock().unwrap().path.is_some();
+        if !persistent {
+            let nonce = self.next_nonce();
+            return Box::pin(async move { nonce });
+        }
+
+        // Keep the disk sync off the async executor
+        let state = self.state.clone();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || state.lock().unwrap().next())
+                .await
+                .expect("Nonce persistence panicked")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::fs;
+
+    #[test]
+    fn monotonic_across_threads_and_restarts() {
+        let source = Arc::new(MonotonicNonce::new());
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let source = source.clone();
+                std::thread::spawn(move || {
+                    (0..1000)
+                        .map(|_| source.next_nonce().unwrap())
+                        .collect::<Vec<u64>>()
+                })
+            })
+            .collect();
+        let mut nonces = HashSet::new();
+        for thread in threads {
+            let issued = thread.join().unwrap();
+            assert!(issued.windows(2).all(|pair| pair[0] < pair[1]));
+            nonces.extend(issued);
+        }
+        assert_eq!(nonces.len(), 4000);
+
+        let path = std::env::temp_dir().join(format!("kraapi-nonce-{}", std::process::id()));
+        // A nonce far ahead of the clock, i.e. issued before the clock stepped backwards
+        fs::write(&path, "99999999999999999").unwrap();
+        let first = MonotonicNonce::persistent(&path)
+            .unwrap()
+            .next_nonce()
+            .unwrap();
+        let second = MonotonicNonce::persistent(&path)
+            .unwrap()
+            .next_nonce()
+            .unwrap();
+        assert_eq!(first, 100000000000000000);
+        assert_eq!(second, first + 1);
+
+        fs::write(&path, "garbage").unwrap();
+        assert!(MonotonicNonce::persistent(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
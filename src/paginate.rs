@@ -0,0 +1,234 @@
+//! Streams paging through the full result set of the history endpoints
+//!
+//! # Note
+//! [KIClosedOrders], [KITradeHistory] and [KILedgerInfo] return at most 50 results per call,
+//! along with the total `count` of results matching the request.
+//! [closed_orders_stream()][KrakenClient::closed_orders_stream],
+//! [trade_history_stream()][KrakenClient::trade_history_stream] and
+//! [ledger_stream()][KrakenClient::ledger_stream] turn these builders into [Stream]s requesting
+//! one page after the other, advancing `ofs` by the number of results received, until `count`
+//! results were received or a page comes back empty. The filters set on the builder apply to
+//! every page and an offset set on the builder is the offset of the first page.
+//!
+//! Each page is an ordinary request, so it waits on the client's [rate limiter][crate::limiter]
+//! and is [retried][crate::retry] like any other. Pages are only requested as the stream is
+//! polled. Results are yielded newest first. Entries added while paging shift the later pages,
+//! so results that were already yielded are skipped. The stream ends after yielding an error
+//!
+//! ```no_run
+//! use futures_util::stream::StreamExt;
+//! use kraapi::client::KrakenClient;
+//! use kraapi::private::trade_history::KITradeHistory;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KrakenClient::new("<API-Key>", "<API-Secret>");
+//! let mut trades = Box::pin(client.trade_history_stream(KITradeHistory::build()));
+//! while let Some(trade) = trades.next().await {
+//!     let (txid, trade) = trade?;
+//!     println!("{}: {} {}", txid, trade.vol, trade.pair);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use futures_util::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::api::private::closed_orders::{KIClosedOrders, KOClosedOrders, KOOrderInfo};
+use crate::api::private::ledger_info::{KILedgerInfo, KOLedgerHistory, KOLedgerInfo};
+use crate::api::private::trade_history::{KITradeHistory, KOTradeData, KOTradeHistory};
+use crate::api::{timestamp_secs, Input, KTimestamp, KrakenResult, Output, UpdateInput};
+use crate::client::KrakenClient;
+
+// History request whose results can be paged through with the `ofs` parameter
+trait Paginated: Input + UpdateInput + Sized {
+    type Page: Output + DeserializeOwned;
+    type Item;
+
+    // Results of a page, newest first, along with the total number of matching results
+    fn results(page: Self::Page) -> (Vec<(String, Self::Item)>, u32);
+}
+
+impl Paginated for KIClosedOrders {
+    type Page = KOClosedOrders;
+    type Item = KOOrderInfo;
+
+    fn results(page: KOClosedOrders) -> (Vec<(String, KOOrderInfo)>, u32) {
+        let time = |order: &KOOrderInfo| order.closetm.unwrap_or(order.opentm);
+        (newest_first(page.closed, time), page.count)
+    }
+}
+
+impl Paginated for KITradeHistory {
+    type Page = KOTradeHistory;
+    type Item = KOTradeData;
+
+    fn results(page: KOTradeHistory) -> (Vec<(String, KOTradeData)>, u32) {
+        (newest_first(page.closed, |trade| trade.time), page.count)
+    }
+}
+
+impl Paginated for KILedgerInfo {
+    type Page = KOLedgerHistory;
+    type Item = KOLedgerInfo;
+
+    fn results(page: KOLedgerHistory) -> (Vec<(String, KOLedgerInfo)>, u32) {
+        (newest_first(page.ledger, |entry| entry.time), page.count)
+    }
+}
+
+fn newest_first<T, F>(results: HashMap<String, T>, time: F) -> Vec<(String, T)>
+where
+    F: Fn(&T) -> KTimestamp,
+{
+    let mut results: Vec<(String, T)> = results.into_iter().collect();
+    results.sort_by(|(_, a), (_, b)| {
+        timestamp_secs(&time(b))
+            .partial_cmp(&timestamp_secs(&time(a)))
+            .unwrap_or(Ordering::Equal)
+    });
+    results
+}
+
+struct Pager<I: Paginated> {
+    input: Option<I>,
+    offset: u64,
+    count: Option<u64>,
+    results: VecDeque<(String, I::Item)>,
+    seen: HashSet<String>,
+}
+
+impl KrakenClient {
+    /// Stream of every closed order matching `input` as `(txid, order)` pairs, requesting pages
+    /// as needed | See the [module documentation][crate::paginate]
+    pub fn closed_orders_stream(
+        &self,
+        input: KIClosedOrders,
+    ) -> impl Stream<Item = KrakenResult<(String, KOOrderInfo)>> + '_ {
+        self.paginate(input)
+    }
+
+    /// Stream of every trade matching `input` as `(txid, trade)` pairs, requesting pages as
+    /// needed | See the [module documentation][crate::paginate]
+    pub fn trade_history_stream(
+        &self,
+        input: KITradeHistory,
+    ) -> impl Stream<Item = KrakenResult<(String, KOTradeData)>> + '_ {
+        self.paginate(input)
+    }
+
+    /// Stream of every ledger entry matching `input` as `(ledger_id, entry)` pairs, requesting
+    /// pages as needed | See the [module documentation][crate::paginate]
+    pub fn ledger_stream(
+        &self,
+        input: KILedgerInfo,
+    ) -> impl Stream<Item = KrakenResult<(String, KOLedgerInfo)>> + '_ {
+        self.paginate(input)
+    }
+
+    fn paginate<I>(&self, mut input: I) -> impl Stream<Item = KrakenResult<(String, I::Item)>> + '_
+    where
+        I: Paginated + 'static,
+    {
+        let offset = input
+            .list_mut()
+            .get("ofs")
+            .and_then(|ofs| ofs.parse().ok())
+            .unwrap_or(0);
+        let pager = Pager {
+            input: Some(input),
+            offset,
+            count: None,
+            results: VecDeque::new(),
+            seen: HashSet::new(),
+        };
+
+        stream::unfold(pager, move |mut pager| async move {
+            loop {
+                if let Some(result) = pager.results.pop_front() {
+                    return Some((Ok(result), pager));
+                }
+                match pager.count {
+                    Some(count) if pager.offset >= count => return None,
+                    _ => {}
+                }
+
+                let (request, input) = pager
+                    .input
+                    .take()?
+                    .update_input("ofs", pager.offset)
+                    .finish_clone();
+                let page = match self.request::<I::Page>(&request).await {
+                    Ok(page) => page,
+                    Err(err) => return Some((Err(err), pager)),
+                };
+                let (results, count) = I::results(page);
+                if results.is_empty() {
+                    return None;
+                }
+                pager.offset += results.len() as u64;
+                pager.count = Some(count as u64);
+                pager.input = Some(input);
+                let seen = &mut pager.seen;
+                pager.results.extend(
+                    results
+                        .into_iter()
+                        .filter(|(id, _)| seen.insert(id.clone())),
+                );
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+    use futures_util::stream::StreamExt;
+
+    fn trade(time: f64) -> serde_json::Value {
+        serde_json::json!({
+            "ordertxid": "OQCLML-BW3P3-BUCMWZ", "pair": "XXBTZUSD", "time": time, "type": "buy",
+            "ordertype": "limit", "price": "30010.00000", "cost": "600.20000", "fee": "0.00000",
+            "vol": "0.02000000", "margin": "0.00000", "misc": ""
+        })
+    }
+
+    #[tokio::test]
+    async fn page_through_trade_history() {
+        let mock = std::sync::Arc::new(
+            MockTransport::new()
+                .with_result(
+                    "TradesHistory",
+                    serde_json::json!({
+                        "trades": { "T3": trade(1616667003.0), "T2": trade(1616667002.0) },
+                        "count": 3
+                    }),
+                )
+                // A new trade shifted the second page, repeating T2
+                .with_result(
+                    "TradesHistory",
+                    serde_json::json!({
+                        "trades": { "T2": trade(1616667002.0), "T1": trade(1616667001.0) },
+                        "count": 4
+                    }),
+                ),
+        );
+        let client = KrakenClient::with_transport("key", "c2VjcmV0", mock.clone());
+
+        let txids: Vec<String> = client
+            .trade_history_stream(KITradeHistory::build())
+            .map(|trade| trade.unwrap().0)
+            .collect()
+            .await;
+        assert_eq!(txids, vec!["T3", "T2", "T1"]);
+
+        // The count of the second page ends the stream without requesting a third
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].body.contains("ofs=0"));
+        assert!(requests[1].body.contains("ofs=2"));
+    }
+}
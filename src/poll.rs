@@ -0,0 +1,263 @@
+//! Streams polling public market data for new records
+//!
+//! # Note
+//! [KIOHLC], [KIRecentTrades] and [KISpreadData] return the records of a pair since the ID given
+//! to `since()`, along with the ID of the last record to pass to the next request.
+//! [ohlc_stream()][KrakenClient::ohlc_stream],
+//! [recent_trades_stream()][KrakenClient::recent_trades_stream] and
+//! [spread_stream()][KrakenClient::spread_stream] request the data every `interval`, feeding
+//! `last` back into `since()`, and yield every new record once in chronological order. Records
+//! repeated by overlapping responses are dropped. The first request is sent right away, with the
+//! `since` set on the builder if any.
+//!
+//! The current candle returned by the OHLC endpoint keeps changing until its interval is over,
+//! so candles are only yielded once they are committed, i.e. no later than `last`.
+//!
+//! Polling continues after a failed request. The error is yielded and the same request is sent
+//! again after the next `interval`, so drop the stream to stop polling
+//!
+//! ```no_run
+//! use futures_util::stream::StreamExt;
+//! use kraapi::api::asset::{KAsset, KAssetPair};
+//! use kraapi::client::KrakenClient;
+//! use kraapi::public::recent_trades::KIRecentTrades;
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KrakenClient::new("", "");
+//! let input = KIRecentTrades::build(KAssetPair(KAsset::XBT, KAsset::USD));
+//! let mut trades = Box::pin(client.recent_trades_stream(input, Duration::from_secs(5)));
+//! while let Some(trade) = trades.next().await {
+//!     let trade = trade?;
+//!     println!("{} {} @ {}", trade.tradetype, trade.volume, trade.price);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use futures_util::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use crate::api::public::ohlc::{KOOHLCData, KIOHLC, KOOHLC};
+use crate::api::public::recent_trades::{KIRecentTrades, KORecentTrades, KOTradeInfo};
+use crate::api::public::spread_data::{KISpreadData, KOSpreadData, KOSpreadInfo};
+use crate::api::{timestamp_secs, unix_time_secs, Input, KrakenResult, Output, UpdateInput};
+use crate::client::KrakenClient;
+
+// Market data request that can be polled for new records with the `since` parameter
+trait Polled: Input + UpdateInput + Sized {
+    type Response: Output + DeserializeOwned;
+    type Record;
+
+    // Records of a response in chronological order, along with the ID to poll since next
+    fn records(response: Self::Response) -> (Vec<Self::Record>, String);
+
+    // Identity of a record, used to drop records repeated by overlapping responses
+    fn key(record: &Self::Record) -> String;
+
+    // Whether a record is final and can be yielded
+    fn committed(_record: &Self::Record, _last: &str) -> bool {
+        true
+    }
+}
+
+impl Polled for KIOHLC {
+    type Response = KOOHLC;
+    type Record = KOOHLCData;
+
+    fn records(response: KOOHLC) -> (Vec<KOOHLCData>, String) {
+        let records = response.pair.into_values().flatten();
+        (records.collect(), response.last.to_string())
+    }
+
+    fn key(candle: &KOOHLCData) -> String {
+        unix_time_secs(&candle.timestamp).to_string()
+    }
+
+    fn committed(candle: &KOOHLCData, last: &str) -> bool {
+        last.parse()
+            .map_or(true, |last: i64| unix_time_secs(&candle.timestamp) <= last)
+    }
+}
+
+impl Polled for KIRecentTrades {
+    type Response = KORecentTrades;
+    type Record = KOTradeInfo;
+
+    fn records(response: KORecentTrades) -> (Vec<KOTradeInfo>, String) {
+        let records = response.pair.into_values().flatten();
+        (records.collect(), response.last)
+    }
+
+    fn key(trade: &KOTradeInfo) -> String {
+        format!(
+            "{}/{}/{}/{}/{}",
+            timestamp_secs(&trade.time),
+            trade.price,
+            trade.volume,
+            trade.tradetype,
+            trade.ordertype
+        )
+    }
+}
+
+impl Polled for KISpreadData {
+    type Response = KOSpreadData;
+    type Record = KOSpreadInfo;
+
+    fn records(response: KOSpreadData) -> (Vec<KOSpreadInfo>, String) {
+        let records = response.pair.into_values().flatten();
+        (records.collect(), response.last.to_string())
+    }
+
+    fn key(spread: &KOSpreadInfo) -> String {
+        format!(
+            "{}/{}/{}",
+            unix_time_secs(&spread.time),
+            spread.bid,
+            spread.ask
+        )
+    }
+}
+
+struct Poller<I: Polled> {
+    input: Option<I>,
+    interval: Duration,
+    started: bool,
+    records: VecDeque<I::Record>,
+    // Keys of the records of the previous response
+    seen: HashSet<String>,
+}
+
+impl KrakenClient {
+    /// Stream of the committed candles of the pair of `input`, polled every `interval` | See the
+    /// [module documentation][crate::poll]
+    pub fn ohlc_stream(
+        &self,
+        input: KIOHLC,
+        interval: Duration,
+    ) -> impl Stream<Item = KrakenResult<KOOHLCData>> + '_ {
+        self.poll(input, interval)
+    }
+
+    /// Stream of the trades of the pair of `input`, polled every `interval` | See the
+    /// [module documentation][crate::poll]
+    pub fn recent_trades_stream(
+        &self,
+        input: KIRecentTrades,
+        interval: Duration,
+    ) -> impl Stream<Item = KrakenResult<KOTradeInfo>> + '_ {
+        self.poll(input, interval)
+    }
+
+    /// Stream of the spreads of the pair of `input`, polled every `interval` | See the
+    /// [module documentation][crate::poll]
+    pub fn spread_stream(
+        &self,
+        input: KISpreadData,
+        interval: Duration,
+    ) -> impl Stream<Item = KrakenResult<KOSpreadInfo>> + '_ {
+        self.poll(input, interval)
+    }
+
+    fn poll<I>(
+        &self,
+        input: I,
+        interval: Duration,
+    ) -> impl Stream<Item = KrakenResult<I::Record>> + '_
+    where
+        I: Polled + 'static,
+    {
+        let poller = Poller {
+            input: Some(input),
+            interval,
+            started: false,
+            records: VecDeque::new(),
+            seen: HashSet::new(),
+        };
+
+        stream::unfold(poller, move |mut poller| async move {
+            loop {
+                if let Some(record) = poller.records.pop_front() {
+                    return Some((Ok(record), poller));
+                }
+                if poller.started {
+                    tokio::time::sleep(poller.interval).await;
+                }
+                poller.started = true;
+
+                let (request, input) = poller.input.take()?.finish_clone();
+                let response = match self.request::<I::Response>(&request).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        poller.input = Some(input);
+                        return Some((Err(err), poller));
+                    }
+                };
+                let (records, last) = I::records(response);
+                let records: Vec<I::Record> = records
+                    .into_iter()
+                    .filter(|record| I::committed(record, &last))
+                    .collect();
+                let keys: HashSet<String> = records.iter().map(I::key).collect();
+                let seen = &poller.seen;
+                poller.records.extend(
+                    records
+                        .into_iter()
+                        .filter(|record| !seen.contains(&I::key(record))),
+                );
+                poller.seen = keys;
+                poller.input = Some(input.update_input("since", last));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::{KAsset, KAssetPair};
+    use crate::transport::MockTransport;
+    use futures_util::stream::StreamExt;
+
+    fn candle(time: i64) -> serde_json::Value {
+        serde_json::json!([time, "30000.0", "30010.0", "29990.0", "30005.0", "30002.5", "1.5", 12])
+    }
+
+    #[tokio::test]
+    async fn poll_committed_candles() {
+        let mock = std::sync::Arc::new(
+            MockTransport::new()
+                .with_result(
+                    "OHLC",
+                    serde_json::json!({
+                        "XXBTZUSD": [candle(60), candle(120), candle(180)], "last": 120
+                    }),
+                )
+                // Overlaps the previous response, and the current candle is committed now
+                .with_result(
+                    "OHLC",
+                    serde_json::json!({
+                        "XXBTZUSD": [candle(120), candle(180), candle(240)], "last": 180
+                    }),
+                ),
+        );
+        let client = KrakenClient::with_transport("", "", mock.clone());
+
+        let input = KIOHLC::build(KAssetPair(KAsset::XBT, KAsset::USD));
+        let times: Vec<i64> = client
+            .ohlc_stream(input, Duration::from_millis(1))
+            .take(3)
+            .map(|candle| unix_time_secs(&candle.unwrap().timestamp))
+            .collect()
+            .await;
+        assert_eq!(times, vec![60, 120, 180]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].uri.contains("since"));
+        assert!(requests[1].uri.contains("since=120"));
+    }
+}
@@ -0,0 +1,300 @@
+//! Record and replay of API traffic
+//!
+//! # Note
+//! A [RecordingTransport] wraps another [Transport] and appends every request it sends, along
+//! with the raw response body, to a file as one JSON object per line. Only the HTTP method,
+//! endpoint and parameters of a request are recorded. Credentials are sent as headers and are
+//! never written, and the values of the [REDACTED_PARAMS] are replaced so recordings can be
+//! shared. Requests failing before a response was received, i.e. with an HTTP error, are not
+//! recorded.
+//!
+//! A [ReplayTransport] loads such a file and answers requests with the recorded responses in the
+//! order they were recorded, which turns a recorded session into a deterministic regression
+//! fixture. Every request must be for the same endpoint as the next recorded request. Parameters
+//! are not compared since they often contain timestamps and nonces
+//!
+//! ```no_run
+//! use kraapi::client::KrakenClient;
+//! use kraapi::record::ReplayTransport;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Record a session against Kraken
+//! let mut client = KrakenClient::new("<API-Key>", "<API-Secret>");
+//! client.record_to("session.jsonl")?;
+//!
+//! // ...and replay it later without network access
+//! let client = KrakenClient::with_transport("", "", ReplayTransport::open("session.jsonl")?);
+//! # Ok(())
+//! # }
+//! ```
+use futures_util::future::BoxFuture;
+use http::{Method, Request};
+use indexmap::map::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::api::KrakenResult;
+use crate::error::{KError, KrakenErrors};
+use crate::transport::{self, Transport};
+
+/// Parameters whose values are replaced with `<redacted>` before a request is recorded
+pub const REDACTED_PARAMS: [&str; 2] = ["nonce", "otp"];
+
+const REDACTED: &str = "<redacted>";
+
+/// Request and raw response recorded by a [RecordingTransport]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RecordedExchange {
+    /// HTTP method, "GET" for public and "POST" for private requests
+    pub method: String,
+    /// Endpoint name, i.e. "Ticker" or "Staking/Assets"
+    pub endpoint: String,
+    /// Url encoded request parameters, in the order they were sent. For requests with a JSON body
+    /// the top level fields, with values other than strings kept as JSON, i.e. the orders of a
+    /// batch
+    pub params: IndexMap<String, String>,
+    /// Raw response body. Base64 encoded if the body isn't valid UTF-8, i.e. export archives
+    pub response: String,
+    /// Whether the response is base64 encoded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+impl RecordedExchange {
+    fn from_request(request: &Request<Vec<u8>>) -> Self {
+        let encoded = match *request.method() {
+            Method::GET => request.uri().query().unwrap_or("").to_string(),
+            _ => String::from_utf8_lossy(request.body()).into_owned(),
+        };
+        // JSON bodies are recorded by their top level fields, with their values as JSON
+        if let Ok(fields) = serde_json::from_str::<Map<String, Value>>(&encoded) {
+            return RecordedExchange::with_params(
+                request,
+                fields.into_iter().map(|(key, value)| {
+                    let value = match value {
+                        Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    (key, value)
+                }),
+            );
+        }
+        let params = encoded
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let mut split = param.splitn(2, '=');
+                let key = split.next().unwrap_or("").to_string();
+                (key, split.next().unwrap_or("").to_string())
+            });
+        RecordedExchange::with_params(request, params)
+    }
+
+    fn with_params<I>(request: &Request<Vec<u8>>, params: I) -> Self
+    where
+        I: Iterator<Item = (String, String)>,
+    {
+        let params = params
+            .map(|(key, value)| {
+                if REDACTED_PARAMS.contains(&key.as_str()) {
+                    (key, REDACTED.to_string())
+                } else {
+                    (key, value)
+                }
+            })
+            .collect();
+
+        RecordedExchange {
+            method: request.method().to_string(),
+            endpoint: transport::endpoint(request),
+            params,
+            response: String::new(),
+            base64: false,
+        }
+    }
+
+    fn set_response(&mut self, body: &[u8]) {
+        match std::str::from_utf8(body) {
+            Ok(body) => {
+                self.response = body.to_string();
+                self.base64 = false;
+            }
+            Err(_) => {
+                self.response = base64::encode(body);
+                self.base64 = true;
+            }
+        }
+    }
+
+    /// Raw response body as it was received
+    pub fn response_body(&self) -> KrakenResult<Vec<u8>> {
+        if self.base64 {
+            base64::decode(&self.response).map_err(|_| KrakenErrors(vec![KError::ReplayMismatch]))
+        } else {
+            Ok(self.response.clone().into_bytes())
+        }
+    }
+}
+
+/// [Transport] recording the traffic of another transport to a file | See the
+/// [module documentation][self]
+pub struct RecordingTransport<T> {
+    inner: T,
+    file: Arc<Mutex<File>>,
+}
+
+impl<T> RecordingTransport<T>
+where
+    T: Transport,
+{
+    /// Record the traffic of `inner` to a new file at `path`, replacing any existing file
+    pub fn create<P>(inner: T, path: P) -> KrakenResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(RecordingTransport::with_file(inner, File::create(path)?))
+    }
+
+    /// Record the traffic of `inner` to an already opened file
+    pub fn with_file(inner: T, file: File) -> Self {
+        RecordingTransport {
+            inner,
+            file: Arc::new(Mutex::new(file)),
+        }
+    }
+}
+
+impl<T> Transport for RecordingTransport<T>
+where
+    T: Transport,
+{
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+        let mut exchange = RecordedExchange::from_request(&request);
+        Box::pin(async move {
+            let body = self.inner.send(request).await?;
+            exchange.set_response(&body);
+            let mut line = serde_json::to_string(&exchange)?;
+            line.push('\n');
+
+            // Keep the file write off the async executor
+            let file = self.file.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut file = file.lock().unwrap();
+                file.write_all(line.as_bytes())?;
+                file.flush()
+            })
+            .await
+            .expect("Recording the exchange panicked")?;
+            Ok(body)
+        })
+    }
+}
+
+/// [Transport] answering requests with responses recorded by a [RecordingTransport] | See the
+/// [module documentation][self]
+#[derive(Debug)]
+pub struct ReplayTransport {
+    exchanges: Mutex<VecDeque<RecordedExchange>>,
+}
+
+impl ReplayTransport {
+    /// Load the recording at `path`
+    pub fn open<P>(path: P) -> KrakenResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let exchanges = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<RecordedExchange>, _>>()?;
+        Ok(ReplayTransport::from_exchanges(exchanges))
+    }
+
+    /// Replay the given exchanges, i.e. ones built or edited by hand
+    pub fn from_exchanges(exchanges: Vec<RecordedExchange>) -> Self {
+        ReplayTransport {
+            exchanges: Mutex::new(exchanges.into()),
+        }
+    }
+
+    /// Number of recorded responses that haven't been replayed yet
+    pub fn remaining(&self) -> usize {
+        self.exchanges.lock().unwrap().len()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+        let endpoint = transport::endpoint(&request);
+        let mut exchanges = self.exchanges.lock().unwrap();
+        let response = match exchanges.front() {
+            Some(next) if next.endpoint == endpoint && next.method == request.method().as_str() => {
+                exchanges.pop_front().unwrap().response_body()
+            }
+            _ => Err(KrakenErrors(vec![KError::ReplayMismatch])),
+        };
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::private::account_balance::{KIAccountBalance, KOAccountBalance};
+    use crate::api::public::server_time::{KIServerTime, KOServerTime};
+    use crate::client::KrakenClient;
+    use crate::transport::MockTransport;
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let path = std::env::temp_dir().join(format!("kraapi-record-{}.jsonl", std::process::id()));
+        let mock = MockTransport::new()
+            .with_result(
+                "Time",
+                serde_json::json!({ "unixtime": 1616336594, "rfc1123": "Sun, 21 Mar 21 14:23:14 +0000" }),
+            )
+            .with_result("Balance", serde_json::json!({ "ZUSD": "171288.6158" }));
+
+        let mut client = KrakenClient::with_transport("key", "c2VjcmV0", mock);
+        client.record_to(&path).unwrap();
+        let recorded_time = client
+            .request::<KOServerTime>(&KIServerTime::build())
+            .await
+            .unwrap();
+        client
+            .request::<KOAccountBalance>(&KIAccountBalance::build())
+            .await
+            .unwrap();
+
+        let recording = fs::read_to_string(&path).unwrap();
+        assert_eq!(recording.lines().count(), 2);
+        assert!(recording.contains(r#""nonce":"<redacted>""#));
+        assert!(!recording.contains("c2VjcmV0"));
+
+        let replay = std::sync::Arc::new(ReplayTransport::open(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+        let client = KrakenClient::with_transport("key", "c2VjcmV0", replay.clone());
+        // Requests must come in the recorded order
+        assert!(client
+            .request::<KOAccountBalance>(&KIAccountBalance::build())
+            .await
+            .is_err());
+        let time = client
+            .request::<KOServerTime>(&KIServerTime::build())
+            .await
+            .unwrap();
+        assert_eq!(time.unixtime, recorded_time.unixtime);
+        let balance = client
+            .request::<KOAccountBalance>(&KIAccountBalance::build())
+            .await
+            .unwrap();
+        assert_eq!(balance.balances.len(), 1);
+        assert_eq!(replay.remaining(), 0);
+    }
+}
@@ -0,0 +1,268 @@
+//! Runtime registry of the assets and asset pairs listed on Kraken
+//!
+//! # Note
+//! [KAsset] only has variants for the assets known when this crate was released, and asset pair
+//! names can't be split into their base and quote assets reliably without knowing every asset.
+//! An [AssetRegistry] is built from the responses of the
+//! [Get Asset Info][crate::public::asset_info::KIAssetInfo] and
+//! [Get Tradable Asset Pairs][crate::public::asset_pairs::KIAssetPairs] endpoints and maps
+//! Kraken's internal names (`XXBTZUSD`), alternate names (`XBTUSD`) and websocket names
+//! (`XBT/USD`) to the base and quote assets of each pair. Assets without a variant of their own
+//! are represented by [KAsset::Other].
+//!
+//! Once [installed][AssetRegistry::install], the registry is consulted whenever an asset or asset
+//! pair name is parsed, including while deserializing outputs, so newly listed assets and pairs
+//! work without a crate release. Installing a registry replaces the previous one. Load and install
+//! a new one to pick up listings made since
+//!
+//! ```no_run
+//! use kraapi::client::KrakenClient;
+//! use kraapi::registry::AssetRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KrakenClient::new("", "");
+//! AssetRegistry::load(&client).await?.install();
+//! # Ok(())
+//! # }
+//! ```
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::api::asset::{KAsset, KAssetPair};
+use crate::api::public::asset_info::KIAssetInfo;
+use crate::api::public::asset_pairs::KIAssetPairs;
+use crate::api::{Input, KrakenResult, Output};
+use crate::client::KrakenClient;
+
+static INSTALLED: RwLock<Option<Arc<AssetRegistry>>> = RwLock::new(None);
+
+// Names of the assets represented by KAsset::Other. Leaked once so KAsset stays Copy
+static INTERNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// Names Kraken uses for an asset pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairNames {
+    /// Internal name, i.e. `XXBTZUSD`
+    pub name: String,
+    /// Alternate name, i.e. `XBTUSD`
+    pub altname: String,
+    /// Websocket name, i.e. `XBT/USD`. Not every pair is available over websockets
+    pub wsname: Option<String>,
+}
+
+/// Lookup table of assets and asset pairs | See the [module documentation][self]
+#[derive(Debug, Clone, Default)]
+pub struct AssetRegistry {
+    assets: HashMap<String, KAsset>,
+    pairs: HashMap<String, KAssetPair>,
+    names: HashMap<KAssetPair, PairNames>,
+}
+
+#[derive(Deserialize)]
+struct RawAssetInfo {
+    #[serde(flatten)]
+    asset: HashMap<String, RawAsset>,
+}
+
+impl Output for RawAssetInfo {}
+
+#[derive(Deserialize)]
+struct RawAsset {
+    altname: String,
+}
+
+#[derive(Deserialize)]
+struct RawAssetPairInfo {
+    #[serde(flatten)]
+    pair: HashMap<String, RawAssetPair>,
+}
+
+impl Output for RawAssetPairInfo {}
+
+#[derive(Deserialize)]
+struct RawAssetPair {
+    altname: String,
+    wsname: Option<String>,
+    base: String,
+    quote: String,
+}
+
+impl AssetRegistry {
+    /// Construct an empty registry
+    pub fn new() -> Self {
+        AssetRegistry::default()
+    }
+
+    /// Build a registry of every asset and asset pair currently listed on Kraken
+    pub async fn load(client: &KrakenClient) -> KrakenResult<Self> {
+        let assets = client
+            .request::<RawAssetInfo>(&KIAssetInfo::build().finish())
+            .await?;
+        let pairs = client
+            .request::<RawAssetPairInfo>(&KIAssetPairs::build().finish())
+            .await?;
+
+        let mut registry = AssetRegistry::new();
+        for (name, asset) in &assets.asset {
+            registry.insert_asset(name, &asset.altname);
+        }
+        for (name, pair) in &pairs.pair {
+            registry.insert_pair(
+                name,
+                &pair.altname,
+                pair.wsname.as_deref(),
+                &pair.base,
+                &pair.quote,
+            );
+        }
+        Ok(registry)
+    }
+
+    /// Register an asset by its internal name, i.e. `XXBT`, and alternate name, i.e. `XBT`
+    pub fn insert_asset(&mut self, name: &str, altname: &str) -> KAsset {
+        let asset = altname
+            .parse::<KAsset>()
+            .or_else(|_| name.parse::<KAsset>())
+            .unwrap_or_else(|_| KAsset::Other(intern(&altname.to_uppercase())));
+        self.assets.insert(name.to_uppercase(), asset);
+        self.assets.insert(altname.to_uppercase(), asset);
+        asset
+    }
+
+    /// Register an asset pair by its names and the internal names of its base and quote assets,
+    /// i.e. `XXBT` and `ZUSD`. Assets that weren't registered before are registered under their
+    /// internal name
+    pub fn insert_pair(
+        &mut self,
+        name: &str,
+        altname: &str,
+        wsname: Option<&str>,
+        base: &str,
+        quote: &str,
+    ) -> KAssetPair {
+        let base = self
+            .asset(base)
+            .unwrap_or_else(|| self.insert_asset(base, base));
+        let quote = self
+            .asset(quote)
+            .unwrap_or_else(|| self.insert_asset(quote, quote));
+        let pair = KAssetPair(base, quote);
+
+        self.pairs.insert(name.to_uppercase(), pair);
+        self.pairs.insert(altname.to_uppercase(), pair);
+        if let Some(wsname) = wsname {
+            self.pairs.insert(wsname.to_uppercase(), pair);
+        }
+        // Dark pool pairs (".d") share their assets with the regular pair, which keeps its names
+        self.names.entry(pair).or_insert_with(|| PairNames {
+            name: name.to_string(),
+            altname: altname.to_string(),
+            wsname: wsname.map(String::from),
+        });
+        pair
+    }
+
+    /// Asset registered under the internal or alternate name `name`
+    pub fn asset(&self, name: &str) -> Option<KAsset> {
+        self.assets.get(&name.to_uppercase()).copied()
+    }
+
+    /// Asset pair registered under the internal, alternate or websocket name `name`
+    pub fn pair(&self, name: &str) -> Option<KAssetPair> {
+        self.pairs.get(&name.to_uppercase()).copied()
+    }
+
+    /// Names Kraken uses for `pair`, if it is registered
+    pub fn pair_names(&self, pair: &KAssetPair) -> Option<&PairNames> {
+        self.names.get(pair)
+    }
+
+    /// Install this registry to be consulted whenever an asset or asset pair is parsed,
+    /// replacing any previously installed registry
+    pub fn install(self) -> Arc<AssetRegistry> {
+        let registry = Arc::new(self);
+        *INSTALLED.write().unwrap() = Some(registry.clone());
+        registry
+    }
+
+    /// The installed registry, if any
+    pub fn installed() -> Option<Arc<AssetRegistry>> {
+        INSTALLED.read().unwrap().clone()
+    }
+}
+
+// Lookups against the installed registry, used when parsing assets and asset pairs
+pub(crate) fn lookup_asset(name: &str) -> Option<KAsset> {
+    INSTALLED.read().unwrap().as_ref()?.asset(name)
+}
+
+pub(crate) fn lookup_pair(name: &str) -> Option<KAssetPair> {
+    INSTALLED.read().unwrap().as_ref()?.pair(name)
+}
+
+fn intern(name: &str) -> &'static str {
+    let mut interned = INTERNED.lock().unwrap();
+    match interned.get(name) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            interned.insert(name);
+            name
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[tokio::test]
+    async fn resolve_new_listings() {
+        let mock = MockTransport::new()
+            .with_result(
+                "Assets",
+                serde_json::json!({
+                    "XXBT": { "aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5 },
+                    "ZUSD": { "aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2 },
+                    "XPEPE": { "aclass": "currency", "altname": "PEPE", "decimals": 5, "display_decimals": 5 }
+                }),
+            )
+            .with_result(
+                "AssetPairs",
+                serde_json::json!({
+                    "XXBTZUSD": { "altname": "XBTUSD", "wsname": "XBT/USD", "base": "XXBT", "quote": "ZUSD" },
+                    "XPEPEZUSD": { "altname": "PEPEUSD", "wsname": "PEPE/USD", "base": "XPEPE", "quote": "ZUSD" }
+                }),
+            );
+        let client = KrakenClient::with_transport("", "", mock);
+
+        assert!("PEPE".parse::<KAsset>().is_err());
+        let registry = AssetRegistry::load(&client).await.unwrap().install();
+
+        let pepe = KAssetPair(KAsset::Other("PEPE"), KAsset::USD);
+        assert_eq!(registry.asset("XPEPE"), Some(KAsset::Other("PEPE")));
+        assert_eq!(
+            registry.pair("XXBTZUSD"),
+            Some(KAssetPair(KAsset::XBT, KAsset::USD))
+        );
+        assert_eq!("XPEPEZUSD".parse::<KAssetPair>().unwrap(), pepe);
+        assert_eq!("PEPEUSD".parse::<KAssetPair>().unwrap(), pepe);
+        assert_eq!(KAssetPair::from_wsname("PEPE/USD").unwrap(), pepe);
+        assert_eq!(pepe.to_string(), "PEPEUSD");
+        assert_eq!(
+            registry.pair_names(&pepe).unwrap().wsname.as_deref(),
+            Some("PEPE/USD")
+        );
+
+        let balances: HashMap<KAsset, String> =
+            serde_json::from_str(r#"{ "XPEPE": "1.5", "ZUSD": "10" }"#).unwrap();
+        assert_eq!(balances[&KAsset::Other("PEPE")], "1.5");
+        assert_eq!(
+            serde_json::to_string(&KAsset::Other("PEPE")).unwrap(),
+            r#""PEPE""#
+        );
+    }
+}
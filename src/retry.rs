@@ -0,0 +1,272 @@
+//! Retry policy for requests failing with transient errors
+//!
+//! # Note
+//! A [RetryPolicy] set on a [KrakenClient][crate::client::KrakenClient] resends requests that
+//! failed with a retryable error after an exponentially growing, jittered delay. Private requests
+//! are signed with a fresh nonce on every attempt.
+//!
+//! Requests that are not idempotent (withdrawals, transfers, staking, batch and edited orders)
+//! are never retried since a failed response doesn't guarantee Kraken didn't act on them.
+//! [AddOrder][crate::private::add_order::KIAddOrder] requests are only retried when they carry a
+//! userref: before each retry the open and closed orders with that userref are queried and, if
+//! an order with the same pair, type, order type, volume and price placed by the failed attempt
+//! is found, it is returned instead of placing the order a second time. Use a userref that is
+//! unique to each order for this check to be reliable. Requests for a new deposit address are
+//! never retried either
+use indexmap::map::IndexMap;
+use std::time::{Duration, SystemTime};
+
+use crate::api::asset::KAssetPair;
+use crate::api::private::KOOrderInfo;
+use crate::api::{KrakenInput, MethodType};
+use crate::error::{KError, KrakenErrors};
+
+/// Policy deciding whether and when a failed request is retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retryable: fn(&KError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts in total, backing off from 500ms up to 10s with jitter, retrying
+    /// [default_retryable] errors
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable: default_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Construct the [default][RetryPolicy::default] retry policy
+    pub fn new() -> Self {
+        RetryPolicy::default()
+    }
+
+    /// Maximum number of attempts including the first one. 1 disables retries
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry. The delay doubles on every following retry
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound of the delay between two attempts
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Whether to randomize each delay between zero and the exponential backoff, which spreads
+    /// out retries from many clients failing at the same time
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Predicate deciding which errors are worth retrying. Defaults to [default_retryable]
+    pub fn with_retryable(mut self, retryable: fn(&KError) -> bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Maximum number of attempts including the first one
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether a request failing with `errors` should be retried. Every error returned must be
+    /// retryable
+    pub fn is_retryable(&self, errors: &KrakenErrors<KError>) -> bool {
+        !errors.0.is_empty() && errors.0.iter().all(|err| (self.retryable)(err))
+    }
+
+    /// Delay before the given retry, starting at 1 for the first retry
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Errors retried by the default policy: Kraken being unavailable, busy or failing internally,
+/// HTTP errors such as a dropped connection, and timeouts
+pub fn default_retryable(error: &KError) -> bool {
+    matches!(
+        error,
+        KError::ServiceUnavailable
+            | KError::ServiceBusy
+            | KError::InternalError
+            | KError::HttpError(_)
+            | KError::Timeout
+    )
+}
+
+// How a request may be retried
+pub(crate) enum Retry {
+    // Resending the request can't have unintended side effects
+    Safe,
+    // An AddOrder request with a userref, which must be checked before resending
+    Dedupe(PlacedOrder),
+    // The request must not be resent
+    Never,
+}
+
+impl Retry {
+    pub(crate) fn of(input: &KrakenInput) -> Self {
+        if let MethodType::Public = input.info().method() {
+            return Retry::Safe;
+        }
+        let param = |key| input.params().and_then(|params| params.get(key));
+        match input.info().endpoint().as_str() {
+            "AddOrder" => match input.params().and_then(PlacedOrder::of) {
+                Some(order) => Retry::Dedupe(order),
+                None => Retry::Never,
+            },
+            // Asks for a new address on every call
+            "DepositAddresses" if param("new").map(String::as_str) == Some("true") => Retry::Never,
+            "AddOrderBatch" | "EditOrder" | "Withdraw" | "WalletTransfer" | "AccountTransfer"
+            | "CreateSubaccount" | "Stake" | "Unstake" | "AddExport" => Retry::Never,
+            _ => Retry::Safe,
+        }
+    }
+}
+
+// Order placed by an AddOrder request with a userref, used to recognize it among the open and
+// closed orders with that userref
+#[derive(Debug)]
+pub(crate) struct PlacedOrder {
+    userref: u32,
+    pair: String,
+    tradetype: String,
+    ordertype: String,
+    volume: Option<f64>,
+    price: Option<f64>,
+}
+
+impl PlacedOrder {
+    fn of(params: &IndexMap<String, String>) -> Option<Self> {
+        let number = |key: &str| params.get(key)?.parse::<f64>().ok();
+        let quote_volume = params
+            .get("oflags")
+            .into_iter()
+            .flat_map(|oflags| oflags.split(','))
+            .any(|flag| flag == "viqc");
+        Some(PlacedOrder {
+            userref: params.get("userref")?.parse().ok()?,
+            pair: params.get("pair")?.clone(),
+            tradetype: params.get("type")?.clone(),
+            ordertype: params.get("ordertype")?.clone(),
+            // The volume of an order is reported in the base currency
+            volume: if quote_volume { None } else { number("volume") },
+            // Relative prices are resolved by Kraken and can't be compared
+            price: number("price"),
+        })
+    }
+
+    pub(crate) fn userref(&self) -> u32 {
+        self.userref
+    }
+
+    // Whether `order` could have been placed by the request
+    pub(crate) fn matches(&self, order: &KOOrderInfo) -> bool {
+        let number = |value: &str| value.parse::<f64>().ok();
+        let pair = match (
+            self.pair.parse::<KAssetPair>(),
+            order.descr.pair.parse::<KAssetPair>(),
+        ) {
+            (Ok(pair), Ok(other)) => pair == other,
+            _ => self.pair.eq_ignore_ascii_case(&order.descr.pair),
+        };
+        order.userref == Some(self.userref)
+            && pair
+            && order.descr.tradetype == self.tradetype
+            && order.descr.ordertype == self.ordertype
+            && (self.volume.is_none() || number(&order.vol.to_string()) == self.volume)
+            && (self.price.is_none() || number(&order.descr.price) == self.price)
+    }
+}
+
+// Cheap random fraction in [0, 1) for jitter. Doesn't need to be cryptographically secure
+fn random_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos();
+    // Scramble the low bits of the clock with a xorshift step
+    let mut x = nanos ^ 0x9E37_79B9;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    f64::from(x) / (f64::from(u32::MAX) + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::{KAsset, KAssetPair};
+    use crate::api::private::add_order::KIAddOrder;
+    use crate::api::private::deposit_addresses::KIDepositAddresses;
+    use crate::api::private::withdraw::KIWithdraw;
+    use crate::api::{Input, OrderType, TradeType};
+
+    #[test]
+    fn retry_policy() {
+        let policy = RetryPolicy::new()
+            .with_jitter(false)
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(350));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+
+        assert!(policy.is_retryable(&KrakenErrors(vec![KError::ServiceBusy])));
+        assert!(!policy.is_retryable(&KrakenErrors(vec![
+            KError::ServiceBusy,
+            KError::InsufficientFunds
+        ])));
+
+        let order = || {
+            KIAddOrder::build(
+                KAssetPair(KAsset::XBT, KAsset::USD),
+                TradeType::Buy,
+                OrderType::Market,
+                1.0,
+            )
+        };
+        assert!(matches!(Retry::of(&order().finish()), Retry::Never));
+        assert!(matches!(
+            Retry::of(&order().with_userref(42).finish()),
+            Retry::Dedupe(PlacedOrder { userref: 42, .. })
+        ));
+        let withdraw = KIWithdraw::build(KAsset::XBT, String::from("key"), 1.0).finish();
+        assert!(matches!(Retry::of(&withdraw), Retry::Never));
+        let address = || KIDepositAddresses::build(KAsset::XBT, String::from("Bitcoin"));
+        assert!(matches!(Retry::of(&address().finish()), Retry::Safe));
+        assert!(matches!(
+            Retry::of(&address().generate_new(true).finish()),
+            Retry::Never
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+//! Connect, request and total timeouts for requests sent by a
+//! [KrakenClient][crate::client::KrakenClient]
+//!
+//! # Note
+//! [Timeouts] can be set for every request sent by a client with
+//! [set_timeouts()][crate::client::KrakenClient::set_timeouts] and overridden for a single call
+//! with [request_with_timeouts()][crate::client::KrakenClient::request_with_timeouts]. A request
+//! running out of time fails with [KError::Timeout][crate::error::KError::Timeout]. There are no
+//! timeouts by default.
+//!
+//! - The connect timeout bounds establishing a new connection to Kraken. Requests reusing a
+//!   pooled connection don't connect at all. It is applied by the
+//!   [Transport][crate::transport::Transport], which receives the timeouts as an extension of each
+//!   request
+//! - The request timeout bounds each HTTP attempt, from sending the request until the whole
+//!   response body was received
+//! - The total timeout bounds the whole call, including waiting on the
+//!   [rate limiter][crate::limiter], every retry and the delays between them
+//!
+//! # Timed out orders
+//! A request that timed out may still have reached Kraken and been acted on, so a timeout
+//! doesn't mean an order wasn't placed. When an
+//! [AddOrder][crate::private::add_order::KIAddOrder] request carrying a userref times out, the
+//! client queries the open and closed orders with that userref and returns the order if it finds
+//! one matching the request. Each query is bounded by the request timeout and the whole lookup by
+//! a total timeout of its own, so such a call can take up to twice the total timeout. If no order
+//! is found [KError::Timeout][crate::error::KError::Timeout] is returned, and the order may still
+//! show up later if Kraken was slow to process it. Query the orders with that userref again before
+//! placing it anew, i.e. with [KIOpenOrders][crate::private::open_orders::KIOpenOrders] and
+//! [KIClosedOrders][crate::private::closed_orders::KIClosedOrders].
+//!
+//! Orders without a userref and every other request that isn't safe to resend (see the
+//! [retry module][crate::retry]) are never looked up or retried after a timeout and have to be
+//! reconciled by the caller
+use std::time::Duration;
+
+/// Timeouts applied to requests | See the [module documentation][self]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timeouts {
+    connect: Option<Duration>,
+    request: Option<Duration>,
+    total: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Construct timeouts without any limit set
+    pub fn new() -> Self {
+        Timeouts::default()
+    }
+
+    /// Maximum time to establish a new connection
+    pub fn with_connect(mut self, timeout: Duration) -> Self {
+        self.connect = Some(timeout);
+        self
+    }
+
+    /// Maximum time for each HTTP attempt
+    pub fn with_request(mut self, timeout: Duration) -> Self {
+        self.request = Some(timeout);
+        self
+    }
+
+    /// Maximum time for the whole call, including rate limiting and retries
+    pub fn with_total(mut self, timeout: Duration) -> Self {
+        self.total = Some(timeout);
+        self
+    }
+
+    /// Maximum time to establish a new connection, if any
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    /// Maximum time for each HTTP attempt, if any
+    pub fn request(&self) -> Option<Duration> {
+        self.request
+    }
+
+    /// Maximum time for the whole call, if any
+    pub fn total(&self) -> Option<Duration> {
+        self.total
+    }
+
+    // Timeouts set here, falling back to `defaults` for the ones that aren't
+    pub(crate) fn or(&self, defaults: &Timeouts) -> Timeouts {
+        Timeouts {
+            connect: self.connect.or(defaults.connect),
+            request: self.request.or(defaults.request),
+            total: self.total.or(defaults.total),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! HTTP transports used by [KrakenClient][crate::client::KrakenClient] to send requests
+//!
+//! # Note
+//! [KrakenClient][crate::client::KrakenClient] builds and signs every request itself and hands
+//! the finished HTTP request to a [Transport], which only has to return the raw response body.
+//! Each request carries the [Timeouts] it is sent with as an extension so transports can apply
+//! the connect timeout. Request and total timeouts are applied by the client.
+//! [HyperTransport] sends requests to Kraken over HTTPS and is used by default.
+//! [MockTransport] answers requests with canned responses from memory so input builders, output
+//! types and application logic can be exercised without network access
+use futures_util::future::BoxFuture;
+use http::Request;
+use hyper::client::HttpConnector;
+use hyper::{body, Body, Client};
+use hyper_tls::HttpsConnector;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::api::KrakenResult;
+use crate::error::{KError, KrakenErrors};
+use crate::timeout::Timeouts;
+
+/// Sends a fully built HTTP request and returns the raw response body
+pub trait Transport: Send + Sync {
+    /// Send `request` and resolve to the body of the response
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>>;
+}
+
+/// Shared transports can be inspected after being handed to a client, i.e. to check the
+/// requests received by a [MockTransport]
+impl<T> Transport for Arc<T>
+where
+    T: Transport + ?Sized,
+{
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+        (**self).send(request)
+    }
+}
+
+impl<T> Transport for Box<T>
+where
+    T: Transport + ?Sized,
+{
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+        (**self).send(request)
+    }
+}
+
+// Endpoint name of a request. Paths look like /0/public/Ticker, everything after the method
+// type is the endpoint
+pub(crate) fn endpoint<T>(request: &Request<T>) -> String {
+    request
+        .uri()
+        .path()
+        .split('/')
+        .skip(3)
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
+type HyperClient = Client<HttpsConnector<HttpConnector>, Body>;
+
+/// Default [Transport] sending requests over HTTPS with hyper
+///
+/// The connect timeout is a setting of hyper's connector, so one connection pool is kept per
+/// connect timeout requests are sent with
+pub struct HyperTransport {
+    clients: Mutex<HashMap<Option<Duration>, HyperClient>>,
+}
+
+impl HyperTransport {
+    /// Construct a new hyper transport with its own connection pool
+    pub fn new() -> Self {
+        HyperTransport {
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn client(&self, connect: Option<Duration>) -> HyperClient {
+        self.clients
+            .lock()
+            .unwrap()
+            .entry(connect)
+            .or_insert_with(|| {
+                let mut http = HttpConnector::new();
+                http.enforce_http(false);
+                http.set_connect_timeout(connect);
+                Client::builder()
+                    .pool_idle_timeout(None)
+                    .http1_title_case_headers(true)
+                    .build::<_, Body>(HttpsConnector::new_with_connector(http))
+            })
+            .clone()
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        HyperTransport::new()
+    }
+}
+
+impl Transport for HyperTransport {
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+        let connect = request
+            .extensions()
+            .get::<Timeouts>()
+            .and_then(Timeouts::connect);
+        let client = self.client(connect);
+        let (parts, bytes) = request.into_parts();
+        let request = Request::from_parts(parts, Body::from(bytes));
+        Box::pin(async move {
+            let response = client.request(request).await.map_err(connect_error)?;
+            Ok(body::to_bytes(response).await?.to_vec())
+        })
+    }
+}
+
+// Report connect timeouts as KError::Timeout rather than an HTTP error
+fn connect_error(err: hyper::Error) -> KrakenErrors<KError> {
+    let mut source = err.source();
+    while let Some(cause) = source {
+        if let Some(io) = cause.downcast_ref::<io::Error>() {
+            if io.kind() == io::ErrorKind::TimedOut {
+                return KrakenErrors(vec![KError::Timeout]);
+            }
+        }
+        source = cause.source();
+    }
+    err.into()
+}
+
+/// Request received by a [MockTransport]
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Endpoint name, i.e. "Ticker" or "Staking/Assets"
+    pub endpoint: String,
+    /// HTTP method
+    pub method: String,
+    /// Full request uri
+    pub uri: String,
+    /// Request body. Holds the url encoded parameters of private requests
+    pub body: String,
+}
+
+/// In-memory [Transport] answering requests with canned responses per endpoint
+///
+/// Responses queued for an endpoint are returned in order. The last response is repeated once
+/// the queue is down to a single response. Requests to endpoints without any response get
+/// Kraken's unknown method error. Every request received is recorded and can be inspected with
+/// [requests()][MockTransport::requests]
+#[derive(Default)]
+pub struct MockTransport {
+    responses: Mutex<HashMap<String, VecDeque<Vec<u8>>>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl MockTransport {
+    /// Construct a mock transport without any responses
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Queue a raw response body for `endpoint`
+    pub fn with_response<T>(self, endpoint: &str, body: T) -> Self
+    where
+        T: Into<Vec<u8>>,
+    {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(endpoint.to_string())
+            .or_default()
+            .push_back(body.into());
+        self
+    }
+
+    /// Queue a successful response for `endpoint` wrapping `result` the way Kraken does
+    pub fn with_result(self, endpoint: &str, result: serde_json::Value) -> Self {
+        let body = serde_json::json!({ "error": [], "result": result });
+        self.with_response(endpoint, body.to_string())
+    }
+
+    /// Queue an error response for `endpoint` with the given Kraken error messages, i.e.
+    /// "EService:Unavailable"
+    pub fn with_errors(self, endpoint: &str, errors: &[&str]) -> Self {
+        let body = serde_json::json!({ "error": errors });
+        self.with_response(endpoint, body.to_string())
+    }
+
+    /// Every request received so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, KrakenResult<Vec<u8>>> {
+        let endpoint = endpoint(&request);
+        self.requests.lock().unwrap().push(MockRequest {
+            endpoint: endpoint.clone(),
+            method: request.method().to_string(),
+            uri: request.uri().to_string(),
+            body: String::from_utf8_lossy(request.body()).into_owned(),
+        });
+
+        let response = match self.responses.lock().unwrap().get_mut(&endpoint) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        let body = response.unwrap_or_else(|| {
+            serde_json::json!({ "error": ["EGeneral:Unknown method"] })
+                .to_string()
+                .into_bytes()
+        });
+        Box::pin(async move { Ok(body) })
+    }
+}
@@ -0,0 +1,303 @@
+//! Asynchronous websocket clients streaming data from Kraken's websocket API
+//!
+//! # Note
+//! Where [KrakenClient][super::client::KrakenClient] polls the REST API one request at a time,
+//! the websocket clients hold a single connection open and yield messages as an asynchronous
+//! [Stream][futures_util::stream::Stream] as soon as Kraken pushes them. Channels are
+//! subscribed to with the same [KAssetPair][super::api::asset::KAssetPair] type used by the
+//! REST input builders
+use futures_util::sink::SinkExt;
+use futures_util::stream::StreamExt;
+use futures_util::{ready, task::Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::{Error as WebSocketError, Message};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use crate::api::KrakenResult;
+use crate::error;
+
+/// Public market data websocket client
+pub mod public;
+
+/// Authenticated websocket client for account data
+pub mod private;
+
+/// Status of a subscription request | See [KOWSSubscriptionStatus]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KWSSubscriptionState {
+    /// Channel was successfully subscribed to
+    Subscribed,
+    /// Channel was successfully unsubscribed from
+    Unsubscribed,
+    /// Subscription request failed. See the error message for details
+    Error,
+}
+
+/// Subscription details echoed back by Kraken | See [KOWSSubscriptionStatus]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSSubscriptionInfo {
+    /// Channel name
+    pub name: String,
+    /// OHLC interval in minutes (if subscribed to OHLC)
+    pub interval: Option<u32>,
+    /// Book depth (if subscribed to book)
+    pub depth: Option<u32>,
+}
+
+/// Response to a subscribe or unsubscribe request
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSSubscriptionStatus {
+    /// Channel ID on successful subscription
+    #[serde(rename = "channelID")]
+    pub channel_id: Option<u64>,
+    /// Channel name on successful subscription. For payloads 'ohlc' and 'book', respective
+    /// interval or depth will be added as suffix
+    #[serde(rename = "channelName")]
+    pub channel_name: Option<String>,
+    /// Websocket name of the pair subscribed to
+    pub pair: Option<String>,
+    /// Status of the subscription request
+    pub status: KWSSubscriptionState,
+    /// Subscription details
+    pub subscription: Option<KOWSSubscriptionInfo>,
+    /// Client originated request ID sent with the request
+    pub reqid: Option<u32>,
+    /// Error message (if the request failed)
+    #[serde(rename = "errorMessage")]
+    pub error_message: Option<String>,
+}
+
+/// Status of Kraken's websocket API sent on connection and on any status change
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSSystemStatus {
+    /// Connection ID for this websocket session
+    #[serde(rename = "connectionID")]
+    pub connection_id: Option<u64>,
+    /// online | maintenance | cancel_only | limit_only | post_only
+    pub status: String,
+    /// Websocket API version
+    pub version: String,
+}
+
+/// Non-data messages sent by Kraken's websocket API
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum KWSEvent {
+    /// Sent by the server when no subscription traffic has been sent for about one second
+    Heartbeat,
+    /// Response to a client ping
+    Pong {
+        /// Client originated request ID sent with the ping
+        reqid: Option<u32>,
+    },
+    /// Connection status, sent on connection and on status changes
+    SystemStatus(KOWSSystemStatus),
+    /// Response to a subscribe or unsubscribe request
+    SubscriptionStatus(KOWSSubscriptionStatus),
+    /// Generic error for malformed requests
+    Error {
+        /// Error message
+        #[serde(rename = "errorMessage")]
+        error_message: String,
+        /// Client originated request ID sent with the request
+        reqid: Option<u32>,
+    },
+}
+
+// Thin wrapper around the websocket stream shared by the public and private clients. Handles
+// the transport details so the clients only deal with Kraken's JSON messages
+pub(crate) struct WsConnection {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WsConnection {
+    pub(crate) async fn connect(url: &str) -> KrakenResult<Self> {
+        let (stream, _) = connect_async(url).await?;
+        Ok(WsConnection { stream })
+    }
+
+    pub(crate) async fn send<T>(&mut self, message: &T) -> KrakenResult<()>
+    where
+        T: Serialize,
+    {
+        let text = serde_json::to_string(message)?;
+        self.stream.send(Message::Text(text)).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn close(&mut self) -> KrakenResult<()> {
+        self.stream.close(None).await?;
+        Ok(())
+    }
+
+    // Poll for the next text frame. Pings are answered by tungstenite while reading so every
+    // other frame type is skipped. A close frame ends the stream
+    pub(crate) fn poll_text(&mut self, cx: &mut Context<'_>) -> Poll<Option<KrakenResult<String>>> {
+        loop {
+            match ready!(self.stream.poll_next_unpin(cx)) {
+                Some(Ok(Message::Text(text))) => return Poll::Ready(Some(Ok(text))),
+                Some(Ok(Message::Close(_))) | None => return Poll::Ready(None),
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+            }
+        }
+    }
+}
+
+type PendingRequests = Arc<Mutex<HashMap<u32, oneshot::Sender<Value>>>>;
+
+// Connection driven by a background task so requests can be sent through a shared reference
+// while the message stream is being polled. Responses carrying the reqid of a pending request
+// are routed back to that request instead of the message stream. Both channels are unbounded:
+// the background task must keep reading so responses reach their requests even while nobody
+// polls the message stream, so unpolled messages pile up in `incoming` instead
+pub(crate) struct WsSession {
+    outgoing: mpsc::UnboundedSender<Message>,
+    incoming: mpsc::UnboundedReceiver<KrakenResult<Value>>,
+    pending: PendingRequests,
+    reqid: AtomicU32,
+}
+
+impl WsSession {
+    pub(crate) async fn connect(url: &str) -> KrakenResult<Self> {
+        let (stream, _) = connect_async(url).await?;
+        let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+        let pending = PendingRequests::default();
+
+        tokio::spawn(WsSession::run(
+            stream,
+            outgoing_rx,
+            incoming_tx,
+            pending.clone(),
+        ));
+
+        Ok(WsSession {
+            outgoing,
+            incoming,
+            pending,
+            reqid: AtomicU32::new(1),
+        })
+    }
+
+    async fn run(
+        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        mut outgoing: mpsc::UnboundedReceiver<Message>,
+        incoming: mpsc::UnboundedSender<KrakenResult<Value>>,
+        pending: PendingRequests,
+    ) {
+        let (mut sink, mut stream) = stream.split();
+        loop {
+            tokio::select! {
+                message = outgoing.recv() => match message {
+                    Some(message) => {
+                        if let Err(err) = sink.send(message).await {
+                            let _ = incoming.send(Err(err.into()));
+                            break;
+                        }
+                    }
+                    // The session was dropped
+                    None => {
+                        let _ = sink.close().await;
+                        break;
+                    }
+                },
+                frame = stream.next() => match frame {
+                    Some(Ok(Message::Text(text))) => {
+                        let message = match serde_json::from_str::<Value>(&text) {
+                            Ok(message) => message,
+                            Err(err) => {
+                                let _ = incoming.send(Err(err.into()));
+                                continue;
+                            }
+                        };
+                        let waiting = message
+                            .get("reqid")
+                            .and_then(Value::as_u64)
+                            .and_then(|reqid| pending.lock().unwrap().remove(&(reqid as u32)));
+                        match waiting {
+                            Some(request) => {
+                                let _ = request.send(message);
+                            }
+                            None => {
+                                let _ = incoming.send(Ok(message));
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => {
+                        let _ = incoming.send(Err(err.into()));
+                        break;
+                    }
+                },
+            }
+        }
+        // Dropping the senders fails every request still waiting on a response
+        pending.lock().unwrap().clear();
+    }
+
+    pub(crate) fn send<T>(&self, message: &T) -> KrakenResult<()>
+    where
+        T: Serialize,
+    {
+        let text = serde_json::to_string(message)?;
+        self.outgoing
+            .send(Message::Text(text))
+            .map_err(|_| WebSocketError::ConnectionClosed.into())
+    }
+
+    // Send `message` with a fresh reqid and wait for the response carrying the same reqid.
+    // Responses with an error status are converted into KrakenErrors
+    pub(crate) async fn request(&self, mut message: Value) -> KrakenResult<Value> {
+        let reqid = self.reqid.fetch_add(1, Ordering::Relaxed);
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(reqid, response_tx);
+
+        message["reqid"] = Value::from(reqid);
+        if let Err(err) = self.send(&message) {
+            self.pending.lock().unwrap().remove(&reqid);
+            return Err(err);
+        }
+
+        let response = response_rx
+            .await
+            .map_err(|_| error::KrakenErrors::from(WebSocketError::ConnectionClosed))?;
+        match response.get("status").and_then(Value::as_str) {
+            Some("error") => {
+                let message = response
+                    .get("errorMessage")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                Err(error::generate_errors(vec![message.to_string()]))
+            }
+            _ => Ok(response),
+        }
+    }
+
+    pub(crate) fn close(&self) -> KrakenResult<()> {
+        self.outgoing
+            .send(Message::Close(None))
+            .map_err(|_| WebSocketError::ConnectionClosed.into())
+    }
+
+    pub(crate) fn poll_message(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<KrakenResult<Value>>> {
+        self.incoming.poll_recv(cx)
+    }
+}
+
+// Build a serde_json error for websocket messages that don't match any known layout
+pub(crate) fn malformed(msg: &str) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
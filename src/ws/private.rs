@@ -0,0 +1,808 @@
+use futures_util::stream::Stream;
+use futures_util::task::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::pin::Pin;
+use std::task::Poll;
+
+use super::{malformed, KWSEvent, WsSession};
+use crate::api::asset::KAssetPair;
+use crate::api::private::add_order::Leverage;
+use crate::api::private::cancel_all_orders::KOCancelAllOrders;
+use crate::api::private::cancel_on_timeout::KOCancelOnTimeout;
+use crate::api::private::websockets_token::{KIWebSocketsToken, KOWebSocketsToken};
+use crate::api::private::{KOOrderDescription, KOOrderInfo, KOOrderStatus, KOTradeData};
+use crate::api::{KDecimal, KTimestamp, KrakenResult, OrderFlags, OrderType, TradeType};
+use crate::client::KrakenClient;
+
+/// Kraken's authenticated websocket endpoint
+pub const PRIVATE_URL: &str = "wss://ws-auth.kraken.com";
+
+/// Private account data channels | See [KWSPrivate::subscribe]
+pub enum PrivateChannel {
+    /// Trades belonging to the authenticated user. A snapshot of the 50 most recent trades is
+    /// sent on subscription
+    OwnTrades,
+    /// Open orders belonging to the authenticated user. A snapshot of all open orders is sent on
+    /// subscription followed by (partial) updates on any change
+    OpenOrders,
+}
+
+impl PrivateChannel {
+    fn subscription(&self, token: &str) -> Value {
+        match self {
+            PrivateChannel::OwnTrades => json!({ "name": "ownTrades", "token": token }),
+            PrivateChannel::OpenOrders => json!({ "name": "openOrders", "token": token }),
+        }
+    }
+}
+
+/// Trade data streamed on the ownTrades channel. Convertible into [KOTradeData]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSOwnTrade {
+    /// Order responsible for execution of trade
+    pub ordertxid: String,
+    /// Position trade id
+    pub postxid: Option<String>,
+    /// Websocket name of the asset pair
+    pub pair: String,
+    #[serde(deserialize_with = "crate::api::deserialize_timestamp")]
+    pub time: KTimestamp,
+    #[serde(rename = "type")]
+    pub tradetype: String,
+    pub ordertype: String,
+    pub price: KDecimal,
+    pub cost: KDecimal,
+    pub fee: KDecimal,
+    pub vol: KDecimal,
+    pub margin: Option<KDecimal>,
+}
+
+impl From<KOWSOwnTrade> for KOTradeData {
+    fn from(trade: KOWSOwnTrade) -> Self {
+        KOTradeData {
+            ordertxid: trade.ordertxid,
+            pair: trade.pair,
+            time: trade.time,
+            tradetype: trade.tradetype,
+            ordertype: trade.ordertype,
+            price: trade.price,
+            cost: trade.cost,
+            fee: trade.fee,
+            vol: trade.vol,
+            margin: trade.margin,
+            misc: String::new(),
+            posstatus: None,
+            cprice: None,
+            cfee: None,
+            cvol: None,
+            cmargin: None,
+            net: None,
+            trades: None,
+        }
+    }
+}
+
+/// Order description streamed on the openOrders channel | See [KOWSOrderUpdate]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSOrderDescription {
+    pub pair: Option<String>,
+    /// Position ID (if applicable)
+    pub position: Option<String>,
+    #[serde(rename = "type")]
+    pub tradetype: Option<String>,
+    pub ordertype: Option<String>,
+    pub price: Option<String>,
+    pub price2: Option<String>,
+    pub leverage: Option<String>,
+    #[serde(rename = "order")]
+    pub desc: Option<String>,
+    #[serde(rename = "close")]
+    pub closedesc: Option<String>,
+}
+
+impl From<KOWSOrderDescription> for KOOrderDescription {
+    fn from(descr: KOWSOrderDescription) -> Self {
+        KOOrderDescription {
+            pair: descr.pair.unwrap_or_default(),
+            tradetype: descr.tradetype.unwrap_or_default(),
+            ordertype: descr.ordertype.unwrap_or_default(),
+            price: descr.price.unwrap_or_default(),
+            price2: descr.price2.unwrap_or_default(),
+            leverage: descr.leverage.unwrap_or_default(),
+            desc: descr.desc.unwrap_or_default(),
+            closedesc: descr.closedesc.unwrap_or_default(),
+        }
+    }
+}
+
+/// Order data streamed on the openOrders channel
+///
+/// The initial snapshot contains every field and can be converted into a [KOOrderInfo] with
+/// [TryFrom]. Subsequent updates only carry the fields that changed and should be merged into a
+/// previously converted order with [apply_to()][KOWSOrderUpdate::apply_to]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSOrderUpdate {
+    /// Referral order transaction id that created this order
+    pub refid: Option<String>,
+    /// user reference id
+    pub userref: Option<u32>,
+    /// status of order
+    pub status: Option<KOOrderStatus>,
+    /// unix timestamp of when order was placed
+    #[serde(default, deserialize_with = "crate::api::deserialize_opt_timestamp")]
+    pub opentm: Option<KTimestamp>,
+    /// unix timestamp of order start time, or 0 (the Unix epoch with the `timestamps` feature) if
+    /// not set
+    #[serde(default, deserialize_with = "crate::api::deserialize_opt_timestamp")]
+    pub starttm: Option<KTimestamp>,
+    /// unix timestamp of order end time, or 0 (the Unix epoch with the `timestamps` feature) if
+    /// not set
+    #[serde(default, deserialize_with = "crate::api::deserialize_opt_timestamp")]
+    pub expiretm: Option<KTimestamp>,
+    /// unix timestamp of the last change to this order
+    #[serde(default, deserialize_with = "crate::api::deserialize_opt_timestamp")]
+    pub lastupdated: Option<KTimestamp>,
+    /// order description info
+    pub descr: Option<KOWSOrderDescription>,
+    /// volume of order (base currency unless viqc set in oflags)
+    pub vol: Option<KDecimal>,
+    /// volume executed (base currency unless viqc set in oflags)
+    pub vol_exec: Option<KDecimal>,
+    /// total cost (quote currency unless unless viqc set in oflags)
+    pub cost: Option<KDecimal>,
+    /// total fee (quote currency)
+    pub fee: Option<KDecimal>,
+    /// average price (quote currency unless viqc set in oflags)
+    pub avg_price: Option<KDecimal>,
+    /// stop price (quote currency, for trailing stops)
+    pub stopprice: Option<KDecimal>,
+    /// triggered limit price (quote currency, when limit based order type triggered)
+    pub limitprice: Option<KDecimal>,
+    /// comma delimited list of miscellaneous info
+    pub misc: Option<String>,
+    /// comma delimited list of order flags
+    pub oflags: Option<String>,
+    /// reason the order was canceled (if any)
+    pub cancel_reason: Option<String>,
+}
+
+impl KOWSOrderUpdate {
+    /// Merge the fields carried by this update into a previously tracked order
+    pub fn apply_to(self, order: &mut KOOrderInfo) {
+        if let Some(status) = self.status {
+            order.status = status;
+        }
+        if let Some(vol) = self.vol {
+            order.vol = vol;
+        }
+        if let Some(vol_exec) = self.vol_exec {
+            order.vol_exec = vol_exec;
+        }
+        if let Some(cost) = self.cost {
+            order.cost = cost;
+        }
+        if let Some(fee) = self.fee {
+            order.fee = fee;
+        }
+        if let Some(avg_price) = self.avg_price {
+            order.price = avg_price;
+        }
+        if let Some(misc) = self.misc {
+            order.misc = misc;
+        }
+        if let Some(oflags) = self.oflags {
+            order.oflags = oflags;
+        }
+        if self.stopprice.is_some() {
+            order.stopprice = self.stopprice;
+        }
+        if self.limitprice.is_some() {
+            order.limitprice = self.limitprice;
+        }
+        if self.cancel_reason.is_some() {
+            order.reason = self.cancel_reason;
+        }
+    }
+}
+
+impl TryFrom<KOWSOrderUpdate> for KOOrderInfo {
+    /// Partial updates are handed back unchanged
+    type Error = KOWSOrderUpdate;
+
+    fn try_from(update: KOWSOrderUpdate) -> Result<Self, Self::Error> {
+        if update.status.is_none()
+            || update.opentm.is_none()
+            || update.descr.is_none()
+            || update.vol.is_none()
+        {
+            return Err(update);
+        }
+
+        Ok(KOOrderInfo {
+            refid: update.refid,
+            userref: update.userref,
+            status: update.status.unwrap(),
+            opentm: update.opentm.unwrap(),
+            starttm: update.starttm.unwrap_or_default(),
+            expiretm: update.expiretm.unwrap_or_default(),
+            descr: update.descr.unwrap().into(),
+            vol: update.vol.unwrap(),
+            vol_exec: update.vol_exec.unwrap_or_default(),
+            cost: update.cost.unwrap_or_default(),
+            fee: update.fee.unwrap_or_default(),
+            price: update.avg_price.unwrap_or_default(),
+            stopprice: update.stopprice,
+            limitprice: update.limitprice,
+            misc: update.misc.unwrap_or_default(),
+            oflags: update.oflags.unwrap_or_default(),
+            trades: None,
+            closetm: None,
+            reason: update.cancel_reason,
+        })
+    }
+}
+
+/// Messages streamed from the private websocket client
+#[derive(Debug)]
+pub enum KWSPrivateMessage {
+    /// Trades keyed by trade ID, in the order sent by Kraken
+    OwnTrades {
+        trades: Vec<(String, KOWSOwnTrade)>,
+        /// Sequence number of this message on the channel
+        sequence: Option<u64>,
+    },
+    /// Orders keyed by order transaction ID, in the order sent by Kraken
+    OpenOrders {
+        orders: Vec<(String, KOWSOrderUpdate)>,
+        /// Sequence number of this message on the channel
+        sequence: Option<u64>,
+    },
+    /// Heartbeats, status changes and subscription responses
+    Event(KWSEvent),
+}
+
+#[derive(Deserialize)]
+struct Sequence {
+    sequence: u64,
+}
+
+// Private payloads are an array of single entry objects keyed by ID
+fn flatten_payload<T>(payload: &Value) -> KrakenResult<Vec<(String, T)>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let entries = Vec::<HashMap<String, T>>::deserialize(payload)?;
+    Ok(entries.into_iter().flatten().collect())
+}
+
+impl KWSPrivateMessage {
+    pub(crate) fn from_value(message: Value) -> KrakenResult<Self> {
+        let data = match message {
+            Value::Array(data) => data,
+            event => return Ok(KWSPrivateMessage::Event(serde_json::from_value(event)?)),
+        };
+
+        // Channel messages are laid out as [payload, channelName, {"sequence": n}]
+        if data.len() < 2 {
+            return Err(malformed("Channel message is missing fields").into());
+        }
+        let sequence = data
+            .get(2)
+            .and_then(|sequence| Sequence::deserialize(sequence).ok())
+            .map(|sequence| sequence.sequence);
+
+        match data[1].as_str() {
+            Some("ownTrades") => Ok(KWSPrivateMessage::OwnTrades {
+                trades: flatten_payload(&data[0])?,
+                sequence,
+            }),
+            Some("openOrders") => Ok(KWSPrivateMessage::OpenOrders {
+                orders: flatten_payload(&data[0])?,
+                sequence,
+            }),
+            _ => Err(malformed("Unknown channel name").into()),
+        }
+    }
+}
+
+/// Order builder for the websocket addOrder request | See [KWSPrivate::add_order]
+///
+/// Mirrors [KIAddOrder][crate::private::add_order::KIAddOrder] and is built from the same
+/// order types
+pub struct KWSAddOrder {
+    params: Map<String, Value>,
+}
+
+impl KWSAddOrder {
+    /// Constructor returning an order builder for the websocket addOrder request
+    ///
+    /// * `pair` - asset pair for order
+    /// * `tradetype` - [TradeType]
+    /// * `ordertype` - [OrderType]
+    /// * `volume` - order volume in lots, i.e. "1.5"
+    pub fn build(
+        pair: KAssetPair,
+        tradetype: TradeType,
+        ordertype: OrderType,
+        volume: String,
+    ) -> Self {
+        let new = KWSAddOrder { params: Map::new() };
+
+        new.with_pair(pair)
+            .with_transaction_type(tradetype)
+            .with_order_type(ordertype)
+            .with_volume(volume)
+    }
+
+    /// Update the asset pair for this order. Useful for templating
+    pub fn with_pair(self, pair: KAssetPair) -> Self {
+        self.update_input("pair", pair.wsname())
+    }
+
+    /// Update the transaction/trade type for this order. Useful for templating
+    pub fn with_transaction_type(self, tradetype: TradeType) -> Self {
+        self.update_input("type", tradetype.to_string())
+    }
+
+    /// Update the order type for this order. Useful for templating. Note that OrderType encodes
+    /// the desired price (limit price, stop loss trigger price etc.)
+    pub fn with_order_type(mut self, ordertype: OrderType) -> Self {
+        self.params.remove("price");
+        self.params.remove("price2");
+        self.params.remove("displayvol");
+        let (price1, price2) = ordertype.elide();
+        let new = self.update_input("ordertype", ordertype.to_string());
+        let new = match price1 {
+            Some(price) => new.update_input("price", price),
+            None => new,
+        };
+        let new = match price2 {
+            Some(price) => new.update_input("price2", price),
+            None => new,
+        };
+        match ordertype.display_volume() {
+            Some(volume) => new.update_input("displayvol", volume),
+            None => new,
+        }
+    }
+
+    /// Update the order volume in lots
+    pub fn with_volume(self, volume: String) -> Self {
+        self.update_input("volume", volume)
+    }
+
+    /// Update the order volume in lots with an exact decimal volume, truncated to `lot_decimals`
+    /// decimal places. Requires the `decimal` feature
+    #[cfg(feature = "decimal")]
+    pub fn with_decimal_volume(self, volume: rust_decimal::Decimal, lot_decimals: u32) -> Self {
+        self.update_input("volume", crate::api::format_decimal(&volume, lot_decimals))
+    }
+
+    /// Amount of leverage for this order. Subject to [margin trading
+    /// restrictions](https://support.kraken.com/hc/en-us/articles/227876608)
+    pub fn with_leverage(self, leverage: Leverage) -> Self {
+        self.update_input("leverage", leverage.to_string())
+    }
+
+    /// Order flags to set. Accepts any iterable collection of [OrderFlags]
+    pub fn with_order_flags<T>(self, flags: T) -> Self
+    where
+        T: IntoIterator<Item = OrderFlags>,
+    {
+        let mut list: Vec<String> = match self.params.get("oflags").and_then(Value::as_str) {
+            Some(list) => list.split(',').map(String::from).collect(),
+            None => Vec::new(),
+        };
+        for flag in flags {
+            // Silently disallow adding the same flag to the list multiple times
+            if !list.contains(&flag.to_string()) {
+                list.push(flag.to_string());
+            }
+        }
+        self.update_input("oflags", list.join(","))
+    }
+
+    /// Scedule the order start time for `secs` seconds from now
+    pub fn start_in(self, secs: u32) -> Self {
+        self.update_input("starttm", format!("+{}", secs))
+    }
+
+    /// Scedule the order start time for the Unix `timestamp` in seconds
+    pub fn start_at(self, timestamp: u64) -> Self {
+        self.update_input("starttm", timestamp.to_string())
+    }
+
+    /// Order to expire in `secs` seconds
+    pub fn expire_in(self, secs: u32) -> Self {
+        self.update_input("expiretm", format!("+{}", secs))
+    }
+
+    /// Order to expire at the Unix `timestamp` in seconds
+    pub fn expire_at(self, timestamp: u64) -> Self {
+        self.update_input("expiretm", timestamp.to_string())
+    }
+
+    /// User supplied unsigned 32 bit integer which Kraken will use to demarcate this order for
+    /// future reference
+    pub fn with_userref(self, userref: u32) -> Self {
+        self.update_input("userref", userref.to_string())
+    }
+
+    /// Validate inputs on Kraken's servers. Don't submit order
+    pub fn validate(self, validate: bool) -> Self {
+        self.update_input("validate", validate.to_string())
+    }
+
+    /// Closing order to add to the system when this order gets filled. Kraken has no display
+    /// volume for closing orders and rejects iceberg closing orders
+    pub fn with_closing_order(self, ordertype: OrderType) -> Self {
+        match ordertype.elide() {
+            (Some(price1), Some(price2)) => self
+                .update_input("close[ordertype]", ordertype.to_string())
+                .update_input("close[price]", price1)
+                .update_input("close[price2]", price2),
+            (Some(price1), None) => self
+                .update_input("close[ordertype]", ordertype.to_string())
+                .update_input("close[price]", price1),
+            (None, Some(_)) => {
+                unreachable!()
+            }
+            (None, None) => self,
+        }
+    }
+
+    fn update_input(mut self, key: &str, value: String) -> Self {
+        self.params.insert(String::from(key), Value::from(value));
+        self
+    }
+}
+
+/// Response to a websocket addOrder request
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSAddOrder {
+    /// Order ID (if order was added successfully)
+    pub txid: Option<String>,
+    /// Order description info
+    pub descr: Option<String>,
+}
+
+/// Asynchronous websocket client streaming the authenticated user's trades and orders, and
+/// placing or cancelling orders
+///
+/// Sessions are authenticated with a token from the
+/// [GetWebSocketsToken][crate::private::websockets_token::KIWebSocketsToken] endpoint, either
+/// fetched for you by [authenticate()][KWSPrivate::authenticate] or passed in directly.
+///
+/// Order requests are tagged with a unique reqid and the returned future resolves once Kraken
+/// responds with the matching reqid. The connection is driven by a background task so these
+/// futures resolve whether or not the message stream is being polled. Every other message is
+/// buffered until the stream is polled, without bound, so keep polling the stream while
+/// subscribed to a channel
+pub struct KWSPrivate {
+    session: WsSession,
+    token: String,
+}
+
+impl KWSPrivate {
+    /// Request a websockets token with the credentials of `client` and open an authenticated
+    /// connection to Kraken's private websocket API
+    pub async fn authenticate(client: &KrakenClient) -> KrakenResult<Self> {
+        let token = client
+            .request::<KOWebSocketsToken>(&KIWebSocketsToken::build())
+            .await?;
+        KWSPrivate::connect(token.token).await
+    }
+
+    /// Open a connection to Kraken's private websocket API using a previously requested `token`
+    pub async fn connect(token: String) -> KrakenResult<Self> {
+        KWSPrivate::connect_with_url(PRIVATE_URL, token).await
+    }
+
+    /// Open a connection to the websocket server at `url`. Useful for testing against a local
+    /// server
+    pub async fn connect_with_url(url: &str, token: String) -> KrakenResult<Self> {
+        Ok(KWSPrivate {
+            session: WsSession::connect(url).await?,
+            token,
+        })
+    }
+
+    /// Returns the token this session authenticates with
+    pub fn token(&self) -> &String {
+        &self.token
+    }
+
+    /// Subscribe to a private channel. The result of the request is streamed back as a
+    /// [subscription status][super::KOWSSubscriptionStatus] event
+    pub async fn subscribe(&self, channel: PrivateChannel) -> KrakenResult<()> {
+        self.session.send(&json!({
+            "event": "subscribe",
+            "subscription": channel.subscription(&self.token),
+        }))
+    }
+
+    /// Unsubscribe from a previously subscribed private channel
+    pub async fn unsubscribe(&self, channel: PrivateChannel) -> KrakenResult<()> {
+        self.session.send(&json!({
+            "event": "unsubscribe",
+            "subscription": channel.subscription(&self.token),
+        }))
+    }
+
+    /// Place an order. Resolves with the order's transaction ID once Kraken has accepted it
+    pub async fn add_order(&self, order: KWSAddOrder) -> KrakenResult<KOWSAddOrder> {
+        let mut message = Value::Object(order.params);
+        message["event"] = json!("addOrder");
+        message["token"] = json!(self.token);
+        Ok(serde_json::from_value(
+            self.session.request(message).await?,
+        )?)
+    }
+
+    /// Cancel the open order with transaction ID `txid`
+    pub async fn cancel_order(&self, txid: String) -> KrakenResult<()> {
+        self.cancel_order_list(vec![txid]).await
+    }
+
+    /// Cancel every open order in any iterable collection of transaction IDs
+    pub async fn cancel_order_list<T>(&self, txids: T) -> KrakenResult<()>
+    where
+        T: IntoIterator<Item = String>,
+    {
+        let message = json!({
+            "event": "cancelOrder",
+            "token": self.token,
+            "txid": txids.into_iter().collect::<Vec<String>>(),
+        });
+        self.session.request(message).await?;
+        Ok(())
+    }
+
+    /// Cancel all open orders
+    pub async fn cancel_all(&self) -> KrakenResult<KOCancelAllOrders> {
+        let message = json!({ "event": "cancelAll", "token": self.token });
+        Ok(serde_json::from_value(
+            self.session.request(message).await?,
+        )?)
+    }
+
+    /// Cancel all open orders in `timeout` seconds unless the timer is extended or disabled by
+    /// calling again. A timeout of 0 disables the timer
+    pub async fn cancel_on_timeout(&self, timeout: u32) -> KrakenResult<KOCancelOnTimeout> {
+        let message = json!({
+            "event": "cancelAllOrdersAfter",
+            "token": self.token,
+            "timeout": timeout,
+        });
+        Ok(serde_json::from_value(
+            self.session.request(message).await?,
+        )?)
+    }
+
+    /// Application level ping. Resolves with Kraken's [pong][KWSEvent::Pong] event, which
+    /// carries a reqid taken from the same counter as the order requests
+    pub async fn ping(&self) -> KrakenResult<KWSEvent> {
+        let message = json!({ "event": "ping" });
+        Ok(serde_json::from_value(
+            self.session.request(message).await?,
+        )?)
+    }
+
+    /// Close the connection
+    pub async fn close(&self) -> KrakenResult<()> {
+        self.session.close()
+    }
+}
+
+impl Stream for KWSPrivate {
+    type Item = KrakenResult<KWSPrivateMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .session
+            .poll_message(cx)
+            .map(|message| message.map(|message| message.and_then(KWSPrivateMessage::from_value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::KAsset;
+    use crate::error::KError;
+    use futures_util::sink::SinkExt;
+    use futures_util::stream::StreamExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn stream_private_channels() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            for name in ["ownTrades", "openOrders"].iter() {
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                let request: Value = serde_json::from_str(&request).unwrap();
+                assert_eq!(
+                    request,
+                    json!({
+                        "event": "subscribe",
+                        "subscription": { "name": name, "token": "WW91ciBhdXRoZW50aWNhdGlvbiB0b2tlbiBnb2VzIGhlcmUu" },
+                    })
+                );
+            }
+
+            let replies = [
+                r#"[[{"TDLH43-DVQXD-2KHVYY":{"cost":"1000000.00000","fee":"1600.00000","margin":"0.00000","ordertxid":"TDLH43-DVQXD-2KHVYY","ordertype":"limit","pair":"XBT/EUR","postxid":"OGTT3Y-C6I3P-XRI6HX","price":"100000.00000","time":"1560516023.070651","type":"sell","vol":"1000000000.00000000"}}],"ownTrades",{"sequence":1}]"#,
+                r#"[[{"OGTT3Y-C6I3P-XRI6HX":{"avg_price":"34.50000","cost":"0.00000","descr":{"close":"","leverage":"0:1","order":"sell 10.00345345 XBT/EUR @ limit 34.50000 with 0:1 leverage","ordertype":"limit","pair":"XBT/EUR","price":"34.50000","price2":"0.00000","type":"sell"},"expiretm":"0.000000","fee":"0.00000","limitprice":"34.50000","misc":"","oflags":"fcib","opentm":"0.000000","refid":"OKIVMP-5GVZN-Z2D2UA","starttm":"0.000000","status":"open","stopprice":"0.000000","userref":0,"vol":"10.00345345","vol_exec":"0.00000000"}}],"openOrders",{"sequence":1}]"#,
+                r#"[[{"OGTT3Y-C6I3P-XRI6HX":{"status":"canceled","cancel_reason":"User requested"}}],"openOrders",{"sequence":2}]"#,
+            ];
+            for reply in replies.iter() {
+                ws.send(Message::Text(reply.to_string())).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let token = String::from("WW91ciBhdXRoZW50aWNhdGlvbiB0b2tlbiBnb2VzIGhlcmUu");
+        let client = KWSPrivate::connect_with_url(&url, token).await.unwrap();
+        client.subscribe(PrivateChannel::OwnTrades).await.unwrap();
+        client.subscribe(PrivateChannel::OpenOrders).await.unwrap();
+
+        let mut messages = client.map(|message| message.unwrap());
+
+        match messages.next().await.unwrap() {
+            KWSPrivateMessage::OwnTrades {
+                mut trades,
+                sequence,
+            } => {
+                assert_eq!(sequence, Some(1));
+                let (txid, trade) = trades.remove(0);
+                let trade: KOTradeData = trade.into();
+                assert_eq!(txid, "TDLH43-DVQXD-2KHVYY");
+                assert_eq!(trade.tradetype, "sell");
+                assert_eq!(crate::api::timestamp_secs(&trade.time), 1560516023.070651);
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+
+        let mut order = match messages.next().await.unwrap() {
+            KWSPrivateMessage::OpenOrders { mut orders, .. } => {
+                let (_, order) = orders.remove(0);
+                KOOrderInfo::try_from(order).unwrap()
+            }
+            other => panic!("Unexpected message {:?}", other),
+        };
+        assert!(matches!(order.status, KOOrderStatus::Open));
+        assert_eq!(order.descr.pair, "XBT/EUR");
+
+        match messages.next().await.unwrap() {
+            KWSPrivateMessage::OpenOrders {
+                mut orders,
+                sequence,
+            } => {
+                assert_eq!(sequence, Some(2));
+                let (_, update) = orders.remove(0);
+                let update = KOOrderInfo::try_from(update).unwrap_err();
+                update.apply_to(&mut order);
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        assert!(matches!(order.status, KOOrderStatus::Canceled));
+        assert_eq!(order.reason.as_deref(), Some("User requested"));
+        assert_eq!(order.vol.to_string(), "10.00345345");
+
+        assert!(messages.next().await.is_none());
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn iceberg_display_volume() {
+        let pair = KAssetPair(KAsset::XBT, KAsset::USD);
+        let order = KWSAddOrder::build(
+            pair,
+            TradeType::Buy,
+            OrderType::Iceberg(String::from("30000"), String::from("0.1")),
+            String::from("1.5"),
+        );
+        assert_eq!(order.params["displayvol"], "0.1");
+
+        let order = order.with_order_type(OrderType::Limit(String::from("30000")));
+        assert!(!order.params.contains_key("displayvol"));
+    }
+
+    #[tokio::test]
+    async fn correlate_order_responses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let mut requests = Vec::new();
+            for _ in 0..3 {
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                requests.push(serde_json::from_str::<Value>(&request).unwrap());
+            }
+            let ping = requests
+                .iter()
+                .find(|request| request["event"] == "ping")
+                .unwrap();
+            let add_order = requests
+                .iter()
+                .find(|request| request["event"] == "addOrder")
+                .unwrap();
+            let cancel_all = requests
+                .iter()
+                .find(|request| request["event"] == "cancelAll")
+                .unwrap();
+            assert_eq!(add_order["token"], "token");
+            assert_eq!(add_order["pair"], "XBT/USD");
+            assert_eq!(add_order["type"], "buy");
+            assert_eq!(add_order["ordertype"], "stop-loss-limit");
+            assert_eq!(add_order["price"], "#5%");
+            assert_eq!(add_order["price2"], "9000");
+            assert_eq!(add_order["volume"], "1.5");
+            assert_eq!(add_order["leverage"], "2");
+            assert_eq!(add_order["oflags"], "post,fciq");
+
+            // Answer in the opposite order of the requests
+            let replies = [
+                json!({ "event": "pong", "reqid": ping["reqid"] }),
+                json!({ "event": "cancelAllStatus", "status": "ok", "count": 2, "reqid": cancel_all["reqid"] }),
+                json!({ "event": "heartbeat" }),
+                json!({ "event": "addOrderStatus", "status": "ok", "txid": "ONPNXH-KMKMU-F4MR5V", "descr": "buy 1.50000000 XBTUSD @ stop-loss-limit", "reqid": add_order["reqid"] }),
+            ];
+            for reply in replies.iter() {
+                ws.send(Message::Text(reply.to_string())).await.unwrap();
+            }
+
+            let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            let request: Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["event"], "cancelOrder");
+            assert_eq!(request["txid"], json!(["ONPNXH-KMKMU-F4MR5V"]));
+            let reply = json!({ "event": "cancelOrderStatus", "status": "error", "errorMessage": "EOrder:Unknown order", "reqid": request["reqid"] });
+            ws.send(Message::Text(reply.to_string())).await.unwrap();
+            ws.close(None).await.unwrap();
+        });
+
+        let mut client = KWSPrivate::connect_with_url(&url, String::from("token"))
+            .await
+            .unwrap();
+        let order = KWSAddOrder::build(
+            KAssetPair(KAsset::XBT, KAsset::USD),
+            TradeType::Buy,
+            OrderType::StopLossLimit(String::from("#5%"), String::from("9000")),
+            String::from("1.5"),
+        )
+        .with_leverage(Leverage::Two)
+        .with_order_flags(vec![OrderFlags::PostOnly, OrderFlags::QuoteCurrency]);
+
+        let (added, cancelled, pong) =
+            futures_util::join!(client.add_order(order), client.cancel_all(), client.ping());
+        assert_eq!(added.unwrap().txid.as_deref(), Some("ONPNXH-KMKMU-F4MR5V"));
+        assert_eq!(cancelled.unwrap().count, 2);
+        assert!(matches!(pong.unwrap(), KWSEvent::Pong { reqid: Some(_) }));
+
+        let err = client
+            .cancel_order(String::from("ONPNXH-KMKMU-F4MR5V"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.0[0], KError::UnknownError));
+
+        // Only the unsolicited heartbeat reaches the message stream
+        assert!(matches!(
+            client.next().await.unwrap().unwrap(),
+            KWSPrivateMessage::Event(KWSEvent::Heartbeat)
+        ));
+        assert!(client.next().await.is_none());
+        server.await.unwrap();
+    }
+}
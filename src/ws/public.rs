@@ -0,0 +1,504 @@
+use futures_util::stream::Stream;
+use futures_util::task::Context;
+use serde::de::Deserializer;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::pin::Pin;
+use std::task::Poll;
+
+use super::{malformed, KWSEvent, WsConnection};
+use crate::api::asset::KAssetPair;
+use crate::api::{KrakenResult, OHLCInterval};
+
+/// Kraken's public websocket endpoint
+pub const PUBLIC_URL: &str = "wss://ws.kraken.com";
+
+/// Number of price levels to subscribe to on the book channel
+pub enum BookDepth {
+    Ten,
+    TwentyFive,
+    OneHundred,
+    FiveHundred,
+    OneThousand,
+}
+
+impl BookDepth {
+    /// Number of price levels per side
+    pub fn levels(&self) -> u32 {
+        match self {
+            BookDepth::Ten => 10,
+            BookDepth::TwentyFive => 25,
+            BookDepth::OneHundred => 100,
+            BookDepth::FiveHundred => 500,
+            BookDepth::OneThousand => 1000,
+        }
+    }
+}
+
+/// Public market data channels | See [KWSSubscription]
+pub enum Channel {
+    /// Ticker information on currency pair
+    Ticker,
+    /// Open High Low Close (Candle) feed sampled every [interval][OHLCInterval]
+    OHLC(OHLCInterval),
+    /// Trade feed for a currency pair
+    Trade,
+    /// Spread feed showing best bid and offer
+    Spread,
+    /// Order book levels. On subscription a snapshot of the given depth is published, followed by
+    /// incremental updates
+    Book(BookDepth),
+}
+
+impl Channel {
+    fn subscription(&self) -> Value {
+        match self {
+            Channel::Ticker => json!({ "name": "ticker" }),
+            Channel::OHLC(interval) => json!({ "name": "ohlc", "interval": interval.minutes() }),
+            Channel::Trade => json!({ "name": "trade" }),
+            Channel::Spread => json!({ "name": "spread" }),
+            Channel::Book(depth) => json!({ "name": "book", "depth": depth.levels() }),
+        }
+    }
+}
+
+/// Subscription request builder for the public websocket channels
+pub struct KWSSubscription {
+    channel: Channel,
+    pairs: Vec<KAssetPair>,
+    reqid: Option<u32>,
+}
+
+impl KWSSubscription {
+    /// Constructor returning a subscription to `channel` for the asset pair `pair`
+    pub fn build(channel: Channel, pair: KAssetPair) -> Self {
+        KWSSubscription {
+            channel,
+            pairs: Vec::new(),
+            reqid: None,
+        }
+        .with_pair(pair)
+    }
+
+    /// Constructor returning a subscription to `channel` for any iterable collection of asset
+    /// pairs
+    pub fn build_with_list<T>(channel: Channel, pairs: T) -> Self
+    where
+        T: IntoIterator<Item = KAssetPair>,
+    {
+        KWSSubscription {
+            channel,
+            pairs: Vec::new(),
+            reqid: None,
+        }
+        .with_pair_list(pairs)
+    }
+
+    /// Append an asset pair to the subscription
+    pub fn with_pair(mut self, pair: KAssetPair) -> Self {
+        // Silently disallow adding the same pair to the list multiple times
+        if !self.pairs.contains(&pair) {
+            self.pairs.push(pair);
+        }
+        self
+    }
+
+    /// Append any iterable collection of asset pairs to the subscription
+    pub fn with_pair_list<T>(self, pairs: T) -> Self
+    where
+        T: IntoIterator<Item = KAssetPair>,
+    {
+        pairs
+            .into_iter()
+            .fold(self, |sub, pair| sub.with_pair(pair))
+    }
+
+    /// Overwrite the list of asset pairs for this subscription. Useful for templating
+    pub fn update_pair_list<T>(mut self, pairs: T) -> Self
+    where
+        T: IntoIterator<Item = KAssetPair>,
+    {
+        self.pairs.clear();
+        self.with_pair_list(pairs)
+    }
+
+    /// Client originated request ID which will be echoed back in the subscription status
+    pub fn with_reqid(mut self, reqid: u32) -> Self {
+        self.reqid = Some(reqid);
+        self
+    }
+
+    fn message(&self, event: &str) -> Value {
+        let mut message = json!({
+            "event": event,
+            "pair": self.pairs.iter().map(|pair| pair.wsname()).collect::<Vec<String>>(),
+            "subscription": self.channel.subscription(),
+        });
+        if let Some(reqid) = self.reqid {
+            message["reqid"] = json!(reqid);
+        }
+        message
+    }
+}
+
+/// Ticker data
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSTick {
+    /// ask (<price>, <whole lot volume>, <lot volume>)
+    pub a: (String, u64, String),
+    /// bid (<price>, <whole lot volume>, <lot volume>)
+    pub b: (String, u64, String),
+    /// last trade closed array(<price>, <lot volume>)
+    pub c: Vec<String>,
+    /// volume array(<today>, <last 24 hours>)
+    pub v: Vec<String>,
+    /// volume weighted average price array(<today>, <last 24 hours>)
+    pub p: Vec<String>,
+    /// number of trades array(<today>, <last 24 hours>)
+    pub t: Vec<u32>,
+    /// low array(<today>, <last 24 hours>)
+    pub l: Vec<String>,
+    /// high array(<today>, <last 24 hours>)
+    pub h: Vec<String>,
+    /// open array(<today>, <last 24 hours>)
+    pub o: Vec<String>,
+}
+
+/// OHLC candle data
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSOHLC {
+    /// Begin time of interval, in seconds since epoch
+    pub time: String,
+    /// End time of interval, in seconds since epoch
+    pub etime: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub vwap: String,
+    pub volume: String,
+    pub count: i64,
+}
+
+/// Trade data
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSTrade {
+    pub price: String,
+    pub volume: String,
+    /// Time, seconds since epoch
+    pub time: String,
+    /// Triggering order side, buy/sell (b/s)
+    pub side: String,
+    /// Triggering order type market/limit (m/l)
+    pub ordertype: String,
+    pub misc: String,
+}
+
+/// Spread data
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KOWSSpread {
+    /// Best bid price
+    pub bid: String,
+    /// Best ask price
+    pub ask: String,
+    /// Time, seconds since epoch
+    pub timestamp: String,
+    /// Bid volume
+    pub bid_volume: String,
+    /// Ask volume
+    pub ask_volume: String,
+}
+
+/// Price level of the order book | See [KOWSBook]
+#[derive(Serialize, Debug, Clone)]
+pub struct KOWSBookLevel {
+    pub price: String,
+    /// Volume at this price level. A volume of zero removes the level from the book
+    pub volume: String,
+    /// Time, seconds since epoch
+    pub timestamp: String,
+    /// Update is a republish of a level that re-entered the subscribed depth
+    pub republish: bool,
+}
+
+impl<'de> Deserialize<'de> for KOWSBookLevel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut level = Vec::<String>::deserialize(deserializer)?.into_iter();
+        match (level.next(), level.next(), level.next(), level.next()) {
+            (Some(price), Some(volume), Some(timestamp), update_type) => Ok(KOWSBookLevel {
+                price,
+                volume,
+                timestamp,
+                republish: update_type.as_deref() == Some("r"),
+            }),
+            _ => Err(serde::de::Error::custom("Book level is missing fields")),
+        }
+    }
+}
+
+/// Order book snapshot or incremental update
+#[derive(Serialize, Debug)]
+pub struct KOWSBook {
+    /// Message is a snapshot of the book rather than an update
+    pub snapshot: bool,
+    /// Ask side levels
+    pub asks: Vec<KOWSBookLevel>,
+    /// Bid side levels
+    pub bids: Vec<KOWSBookLevel>,
+    /// CRC32 checksum of the top 10 bids and asks after applying this update. Only present on
+    /// updates
+    pub checksum: Option<String>,
+}
+
+// Layout of each object within a book message. Snapshots use "as"/"bs" and updates "a"/"b". An
+// update touching both sides is split across two objects, the last carrying the checksum
+#[derive(Deserialize)]
+struct BookPayload {
+    #[serde(rename = "as")]
+    snapshot_asks: Option<Vec<KOWSBookLevel>>,
+    #[serde(rename = "bs")]
+    snapshot_bids: Option<Vec<KOWSBookLevel>>,
+    a: Option<Vec<KOWSBookLevel>>,
+    b: Option<Vec<KOWSBookLevel>>,
+    c: Option<String>,
+}
+
+impl KOWSBook {
+    fn from_payloads(payloads: &[Value]) -> KrakenResult<Self> {
+        let mut book = KOWSBook {
+            snapshot: false,
+            asks: Vec::new(),
+            bids: Vec::new(),
+            checksum: None,
+        };
+        for payload in payloads {
+            let payload = BookPayload::deserialize(payload)?;
+            if let Some(asks) = payload.snapshot_asks {
+                book.snapshot = true;
+                book.asks.extend(asks);
+            }
+            if let Some(bids) = payload.snapshot_bids {
+                book.snapshot = true;
+                book.bids.extend(bids);
+            }
+            book.asks.extend(payload.a.unwrap_or_default());
+            book.bids.extend(payload.b.unwrap_or_default());
+            if payload.c.is_some() {
+                book.checksum = payload.c;
+            }
+        }
+        Ok(book)
+    }
+}
+
+/// Messages streamed from the public websocket client
+#[derive(Debug)]
+pub enum KWSPublicMessage {
+    Ticker(KAssetPair, KOWSTick),
+    OHLC(KAssetPair, KOWSOHLC),
+    Trade(KAssetPair, Vec<KOWSTrade>),
+    Spread(KAssetPair, KOWSSpread),
+    Book(KAssetPair, KOWSBook),
+    /// Heartbeats, status changes and subscription responses
+    Event(KWSEvent),
+}
+
+impl KWSPublicMessage {
+    fn parse(text: &str) -> KrakenResult<Self> {
+        let message: Value = serde_json::from_str(text)?;
+        let data = match message {
+            Value::Array(data) => data,
+            event => return Ok(KWSPublicMessage::Event(serde_json::from_value(event)?)),
+        };
+
+        // Channel messages are laid out as [channelID, payload(s)..., channelName, pair]
+        let len = data.len();
+        if len < 4 {
+            return Err(malformed("Channel message is missing fields").into());
+        }
+        let (channel, pair) = match (data[len - 2].as_str(), data[len - 1].as_str()) {
+            (Some(channel), Some(pair)) => (channel, KAssetPair::from_wsname(pair)?),
+            _ => return Err(malformed("Channel message is missing its name or pair").into()),
+        };
+        let payload = &data[1];
+
+        match channel {
+            "ticker" => Ok(KWSPublicMessage::Ticker(
+                pair,
+                KOWSTick::deserialize(payload)?,
+            )),
+            "trade" => Ok(KWSPublicMessage::Trade(pair, Vec::deserialize(payload)?)),
+            "spread" => Ok(KWSPublicMessage::Spread(
+                pair,
+                KOWSSpread::deserialize(payload)?,
+            )),
+            _ if channel.starts_with("ohlc") => Ok(KWSPublicMessage::OHLC(
+                pair,
+                KOWSOHLC::deserialize(payload)?,
+            )),
+            _ if channel.starts_with("book") => Ok(KWSPublicMessage::Book(
+                pair,
+                KOWSBook::from_payloads(&data[1..len - 2])?,
+            )),
+            _ => Err(malformed("Unknown channel name").into()),
+        }
+    }
+}
+
+/// Asynchronous websocket client streaming Kraken's public market data
+///
+/// Subscribe to channels with [subscribe()][KWSPublic::subscribe] and consume the typed
+/// messages by polling the client as a [Stream]
+pub struct KWSPublic {
+    connection: WsConnection,
+}
+
+impl KWSPublic {
+    /// Open a connection to Kraken's public websocket API
+    pub async fn connect() -> KrakenResult<Self> {
+        KWSPublic::connect_with_url(PUBLIC_URL).await
+    }
+
+    /// Open a connection to the websocket server at `url`. Useful for testing against a local
+    /// server
+    pub async fn connect_with_url(url: &str) -> KrakenResult<Self> {
+        Ok(KWSPublic {
+            connection: WsConnection::connect(url).await?,
+        })
+    }
+
+    /// Subscribe to a channel. The result of the request is streamed back as a
+    /// [subscription status][super::KOWSSubscriptionStatus] event
+    pub async fn subscribe(&mut self, subscription: &KWSSubscription) -> KrakenResult<()> {
+        self.connection
+            .send(&subscription.message("subscribe"))
+            .await
+    }
+
+    /// Unsubscribe from a previously subscribed channel
+    pub async fn unsubscribe(&mut self, subscription: &KWSSubscription) -> KrakenResult<()> {
+        self.connection
+            .send(&subscription.message("unsubscribe"))
+            .await
+    }
+
+    /// Application level ping. Kraken will respond with a [pong][KWSEvent::Pong] event
+    pub async fn ping(&mut self, reqid: u32) -> KrakenResult<()> {
+        self.connection
+            .send(&json!({ "event": "ping", "reqid": reqid }))
+            .await
+    }
+
+    /// Close the connection
+    pub async fn close(&mut self) -> KrakenResult<()> {
+        self.connection.close().await
+    }
+}
+
+impl Stream for KWSPublic {
+    type Item = KrakenResult<KWSPublicMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .connection
+            .poll_text(cx)
+            .map(|text| text.map(|text| text.and_then(|text| KWSPublicMessage::parse(&text))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::asset::KAsset;
+    use futures_util::sink::SinkExt;
+    use futures_util::stream::StreamExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn stream_public_channels() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            let request: Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(
+                request,
+                json!({
+                    "event": "subscribe",
+                    "pair": ["XBT/USD"],
+                    "subscription": { "name": "book", "depth": 10 },
+                    "reqid": 42,
+                })
+            );
+
+            let replies = [
+                r#"{"channelID":10,"channelName":"book-10","event":"subscriptionStatus","pair":"XBT/USD","status":"subscribed","reqid":42,"subscription":{"name":"book","depth":10}}"#,
+                r#"[10,{"as":[["5541.30000","2.50700000","1534614248.123678"]],"bs":[["5541.20000","1.52900000","1534614248.765567"]]},"book-10","XBT/USD"]"#,
+                r#"[10,{"a":[["5541.30000","0.00000000","1534614335.345903"]]},{"b":[["5541.20000","1.00000000","1534614335.345903","r"]],"c":"974942666"},"book-10","XBT/USD"]"#,
+                r#"[11,{"a":["5525.40000",1,"1.000"],"b":["5525.10000",1,"1.000"],"c":["5525.10000","0.00398963"],"v":["2634.11501494","3591.17907851"],"p":["5631.44067","5653.78939"],"t":[11493,16267],"l":["5505.00000","5505.00000"],"h":["5783.00000","5783.00000"],"o":["5760.70000","5763.40000"]},"ticker","XBT/USD"]"#,
+                r#"{"event":"heartbeat"}"#,
+            ];
+            for reply in replies.iter() {
+                ws.send(Message::Text(reply.to_string())).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let mut client = KWSPublic::connect_with_url(&url).await.unwrap();
+        let pair = KAssetPair(KAsset::XBT, KAsset::USD);
+        client
+            .subscribe(&KWSSubscription::build(Channel::Book(BookDepth::Ten), pair).with_reqid(42))
+            .await
+            .unwrap();
+
+        let messages: Vec<KWSPublicMessage> =
+            client.map(|message| message.unwrap()).collect().await;
+        server.await.unwrap();
+
+        assert_eq!(messages.len(), 5);
+        match &messages[0] {
+            KWSPublicMessage::Event(KWSEvent::SubscriptionStatus(status)) => {
+                assert_eq!(status.channel_name.as_deref(), Some("book-10"));
+                assert_eq!(status.reqid, Some(42));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match &messages[1] {
+            KWSPublicMessage::Book(book_pair, book) => {
+                assert_eq!(*book_pair, pair);
+                assert!(book.snapshot);
+                assert_eq!(book.asks[0].price, "5541.30000");
+                assert_eq!(book.bids[0].volume, "1.52900000");
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match &messages[2] {
+            KWSPublicMessage::Book(_, book) => {
+                assert!(!book.snapshot);
+                assert_eq!(book.asks[0].volume, "0.00000000");
+                assert!(book.bids[0].republish);
+                assert_eq!(book.checksum.as_deref(), Some("974942666"));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match &messages[3] {
+            KWSPublicMessage::Ticker(_, tick) => {
+                assert_eq!(tick.a.0, "5525.40000");
+                assert_eq!(tick.t, vec![11493, 16267]);
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        assert!(matches!(
+            messages[4],
+            KWSPublicMessage::Event(KWSEvent::Heartbeat)
+        ));
+    }
+}